@@ -1,9 +1,11 @@
 use bevy::prelude::*;
 use bitflags::bitflags;
-use crate::{game::GameTimeDelta, combat::Target};
+use serde::Deserialize;
+use crate::{game::GameTimeDelta, combat::{shields::{ShieldCollapsed, ShieldEvent}, targets::InheritTeamFromParent, tools::TargettedTool, turrets::Turret, Target}, math_util::{get_angle_difference, get_heading_to_point}};
 
 bitflags! {
-    #[derive(Default, Component, Copy, Clone)]
+    #[derive(Default, Component, Copy, Clone, Deserialize)]
+    #[serde(transparent)]
     pub struct AgentCategory: u32 {
         const FIGHTER = 0b00000001;
         const FRIGATE = 0b00000010;
@@ -27,11 +29,66 @@ pub struct RetargetBehavior {
 
 pub const MAX_AGGRO_RADIUS : f32 = 1000.0;
 
-#[derive(Copy, Clone, Component)]
+#[derive(Copy, Clone, Component, Deserialize)]
 pub struct TargetingOrders {
     pub preferred: AgentCategory,
     pub discouraged: AgentCategory,
-    pub target_same_team: bool
+    pub target_same_team: bool,
+    /// Only targets in the preferred categories may be chosen.
+    #[serde(default)]
+    pub only_preferred: bool
+}
+
+/// Directions in which a targetter may pick targets.
+#[derive(Copy, Clone, Component)]
+pub struct TargetingArc {
+    /// World heading of the centre of the arc, in radians.
+    pub heading: f32,
+    /// Furthest a target may be from the centre of the arc, in radians.
+    pub half_width: f32
+}
+
+impl Default for TargetingArc {
+    fn default() -> Self {
+        TargetingArc { heading: 0.0, half_width: std::f32::consts::PI }
+    }
+}
+
+impl TargetingArc {
+    /// Whether a target at the given offset from the targetter lies within the arc.
+    pub fn contains(&self, delta: Vec3) -> bool {
+        self.half_width >= std::f32::consts::PI
+            || get_angle_difference(get_heading_to_point(delta), self.heading).abs() <= self.half_width
+    }
+}
+
+/// Components for a hardpoint that picks its own targets, instead of inheriting the target of its parent.
+///
+/// The hardpoint only considers targets within the range of its [TargettedTool], and within the arc it can fire into.
+#[derive(Bundle)]
+pub struct HardpointTargetingBundle {
+    pub orders: TargetingOrders,
+    pub aggro_radius: AggroRadius,
+    pub aggro_location: AggroLocation,
+    pub arc: TargetingArc,
+    pub retarget: RetargetBehavior,
+    pub team: InheritTeamFromParent
+}
+
+impl HardpointTargetingBundle {
+    pub fn new(orders: TargetingOrders, radius: f32, retarget_interval: f32) -> Self {
+        HardpointTargetingBundle {
+            orders,
+            aggro_radius: AggroRadius(radius),
+            aggro_location: AggroLocation::default(),
+            arc: TargetingArc::default(),
+            retarget: RetargetBehavior {
+                interval: retarget_interval,
+                remaining_time: retarget_interval
+            },
+            team: InheritTeamFromParent
+        }
+    }
 }
 
 #[derive(Component)]
@@ -62,6 +119,50 @@ pub fn update_aggression_source(
     }
 }
 
+type HardpointArcQuery<'a> = (
+    &'a mut TargetingArc,
+    &'a mut AggroRadius,
+    &'a GlobalTransform,
+    &'a TargettedTool,
+    Option<&'a Turret>
+);
+
+/// Centres the arc of each hardpoint on its mount, covering its firing cone and any turret traverse, and limits its
+/// aggro radius to the range of its tool.
+pub fn update_targeting_arcs(
+    mut query: Query<HardpointArcQuery>
+) {
+    for (mut arc, mut aggro_radius, transform, tool, turret) in query.iter_mut() {
+        let facing = get_heading_to_point(transform.up());
+        arc.heading = facing - turret.map_or(0.0, |turret| turret.angle);
+        arc.half_width = (0.5 * tool.cone + turret.map_or(0.0, |turret| turret.traverse))
+            .min(std::f32::consts::PI);
+        aggro_radius.0 = tool.range;
+    }
+}
+
+/// Drops targets that have left the range or arc of their targetter, so that it can pick another.
+pub fn release_unreachable_targets(
+    mut query: Query<(
+        &TargetingArc,
+        &AggroLocation,
+        &AggroRadius,
+        &mut Target,
+    )>,
+    pos_query: Query<&GlobalTransform>
+) {
+    for (arc, aggro_loc, aggro_radius, mut target) in query.iter_mut() {
+        let Some(entity) = target.0 else { continue };
+        let reachable = pos_query.get(entity).is_ok_and(|transform| {
+            let delta = transform.translation() - aggro_loc.0;
+            delta.length_squared() <= aggro_radius.0.powi(2) && arc.contains(delta)
+        });
+        if !reachable {
+            target.0 = None;
+        }
+    }
+}
+
 pub fn do_retargetting(
     dt: Res<GameTimeDelta>,
     mut query: Query<(
@@ -79,24 +180,56 @@ pub fn do_retargetting(
     }
 }
 
-use crate::combat::{mortal::{Health, MaxHealth}, Team};
-use multimap::MultiMap;
+/// Makes hostile ships near a collapsed shield pick a new target, so they can take advantage of it.
+pub fn retarget_on_shield_collapse(
+    mut events: EventReader<ShieldEvent>,
+    collapsed_query: Query<(&GlobalTransform, &Team)>,
+    mut query: Query<(
+        &AggroLocation,
+        &AggroRadius,
+        &Team,
+        &TargetingOrders,
+        &mut RetargetBehavior,
+    )>
+) {
+    for event in events.read() {
+        let ShieldEvent::Collapsed(entity) = event else { continue };
+        let Ok((transform, collapsed_team)) = collapsed_query.get(*entity) else { continue };
+        let position = transform.translation();
+
+        for (aggro_loc, aggro_radius, team, orders, mut retarget) in query.iter_mut() {
+            if orders.target_same_team || team == collapsed_team {
+                continue;
+            }
+            if (position - aggro_loc.0).length_squared() > aggro_radius.0.powi(2) {
+                continue;
+            }
+            retarget.remaining_time = 0.0;
+        }
+    }
+}
+
+use crate::{combat::{mortal::{Health, MaxHealth}, Team}, spatial::SpatialHash};
 
-pub const HASH_CELL_SIZE : f32 = 50.0;
+/// Kept here for code written before the spatial hash moved to [crate::spatial].
+pub use crate::spatial::HASH_CELL_SIZE;
 
 struct TargetInformation {
     pub entity: Entity,
     pub category: AgentCategory,
     pub health_fraction: f32,
     pub position: Vec3,
-    pub team: Team
+    pub team: Team,
+    pub shield_down: bool
 }
 
 struct Targetter {
+    pub entity: Entity,
     pub team: Team,
     pub position: Vec3,
     pub orders: TargetingOrders,
     pub radius: f32,
+    pub arc: TargetingArc,
     pub score: f32,
     pub current_target: Target
 }
@@ -108,6 +241,17 @@ impl Targetter {
             return;
         }
 
+        // Allies only need support once they are damaged, and cannot support themselves.
+        if self.orders.target_same_team
+            && (candidate.entity == self.entity || candidate.health_fraction >= 1.0)
+        {
+            return;
+        }
+
+        if self.orders.only_preferred && !self.orders.preferred.contains(candidate.category) {
+            return;
+        }
+
         // Cannot target if outside aggro radius.
         let delta = (candidate.position - self.position).length_squared();
         if delta > self.radius.powi(2)
@@ -115,6 +259,10 @@ impl Targetter {
             return;
         }
 
+        if !self.arc.contains(candidate.position - self.position) {
+            return;
+        }
+
         let mut score = delta;
         if self.orders.target_same_team
         {
@@ -128,6 +276,9 @@ impl Targetter {
         if self.orders.discouraged.contains(candidate.category){
             score *= 5.0;
         }
+        if candidate.shield_down && !self.orders.target_same_team {
+            score /= 2.0;
+        }
 
         if score > self.score{
             return;
@@ -138,49 +289,52 @@ impl Targetter {
     }
 }
 
-/// Convert a position to cell coordinates
-fn get_cell_coordinates(position: Vec3) -> (i32, i32) {
-    ((position.x / HASH_CELL_SIZE).floor() as i32, (position.y / HASH_CELL_SIZE).floor() as i32)
-}
+type TargetCandidate<'a> = (
+    Entity,
+    &'a GlobalTransform,
+    &'a Team,
+    &'a AgentCategory,
+    &'a Health,
+    &'a MaxHealth,
+    Has<ShieldCollapsed>
+);
 
-pub fn find_targets(
-    target_query: Query<(
+type TargetterQuery<'a> = (
     Entity,
-    &GlobalTransform,
-    &Team,
-    &AgentCategory,
-    &Health,
-    &MaxHealth
-    )>,
-    mut targetter_query: Query<(
-        &AggroLocation,
-        &AggroRadius,
-        &Team,
-        &TargetingOrders,
-        &mut Target,
-        )>
+    &'a AggroLocation,
+    &'a AggroRadius,
+    Option<&'a TargetingArc>,
+    &'a Team,
+    &'a TargetingOrders,
+    &'a mut Target,
+);
+
+pub fn find_targets(
+    target_query: Query<TargetCandidate>,
+    mut targetter_query: Query<TargetterQuery>
 ) {
-    let mut sorted_targets = MultiMap::new();
+    let mut sorted_targets = SpatialHash::default();
 
     // Sort valid targets by position into a hashmap.
-    for (entity, transform, team, category, health, max_health) in target_query.iter() {
+    for (entity, transform, team, category, health, max_health, shield_down) in target_query.iter() {
         let health_fraction = health.0 / max_health.0;
         let position = transform.translation();
         sorted_targets.insert(
-            get_cell_coordinates(position),
+            position,
             TargetInformation {
                 entity,
                 category: *category,
                 health_fraction, 
                 position,
-                team: *team
+                team: *team,
+                shield_down
             }
         );
         //println!("Insert {:?} at {:?}", entity, get_cell_coordinates(position));
     }
 
     // Pick best target for each targetter.
-    for (aggro_loc, aggro_radius, team, orders, mut target) in targetter_query.iter_mut() {
+    for (entity, aggro_loc, aggro_radius, arc, team, orders, mut target) in targetter_query.iter_mut() {
         
         if target.0.is_some()
         {
@@ -188,37 +342,40 @@ pub fn find_targets(
         }
 
         let mut targetter = Targetter {
+            entity,
             team: *team,
             position: aggro_loc.0,
             orders: *orders,
             radius: aggro_radius.0,
+            arc: arc.copied().unwrap_or_default(),
             score: std::f32::INFINITY,
             current_target: Target::default()
         };
 
-        let min_coords = get_cell_coordinates(targetter.position - Vec3::splat(targetter.radius));
-        let max_coords = get_cell_coordinates(targetter.position + Vec3::splat(targetter.radius));
-        
-        for x in min_coords.0..=max_coords.0 {
-            for y in min_coords.1..=max_coords.1 {
-                    // Identify bucket to search
-                    let current_bucket = (x,y);
-
-                    // Consider all candidate targets within each bucket.
-                    match sorted_targets.get_vec(&current_bucket) {
-                        None => {continue;}
-                        Some(candidates) => {
-                            for candidate in candidates {
-                                targetter.consider(candidate);
-                            }
-                        }
-                    }
-            }
+        // Consider all candidate targets in the cells around the targetter.
+        for candidate in sorted_targets.near(targetter.position, targetter.radius) {
+            targetter.consider(candidate);
         }
 
         target.0 = targetter.current_target.0;
         //println!("Assigned target: {:?}", target.0);
     }    
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    #[test]
+    fn test_targeting_arc_contains() {
+        // Arc facing left, wrapping across the negative x axis.
+        let arc = TargetingArc { heading: PI, half_width: 0.5 };
+        assert!(arc.contains(Vec3::new(-10.0, 1.0, 0.0)));
+        assert!(arc.contains(Vec3::new(-10.0, -1.0, 0.0)));
+        assert!(!arc.contains(Vec3::new(0.0, 10.0, 0.0)));
+        assert!(!arc.contains(Vec3::new(10.0, 0.0, 0.0)));
 
-    //println!("Number of bins: {:?}", sorted_targets.keys().len());
-}
\ No newline at end of file
+        assert!(TargetingArc::default().contains(Vec3::new(10.0, 0.0, 0.0)));
+    }
+}
@@ -4,6 +4,7 @@ use super::movement::TurnToDestinationBehavior;
 pub struct IdleBehavior;
 use rand::Rng;
 use crate::combat::Target;
+use crate::game::rng::GameplayRng;
 use crate::ai::movement::PursueBehavior;
 
 #[derive(Component)]
@@ -18,9 +19,9 @@ pub fn do_roaming(mut query: Query<(
     &GlobalTransform,
     &RoamBehavior,
     &mut TurnToDestinationBehavior
-), With<IdleBehavior>>) {
-    let mut rng = rand::thread_rng();
-
+), With<IdleBehavior>>,
+    mut rng: ResMut<GameplayRng>,
+) {
     for (transform, roam, mut turn_to_destination) in query.iter_mut() {
 
         let delta = turn_to_destination.destination - transform.translation();
@@ -18,18 +18,25 @@ impl Plugin for AIPlugin {
             (
                 movement::peel_manoeuvre,
                 movement::pursue,
+                movement::turn_damaged_shield_facing_away
+                    .after(movement::pursue)
+                    .before(movement::turn_to_destination),
                 movement::turn_to_destination
                     .after(crate::movement::update_heading)
                     .before(crate::movement::update_rotation),
                 idle::do_roaming,
                 (
                     aggression::update_aggression_source,
+                    aggression::update_targeting_arcs,
+                    aggression::retarget_on_shield_collapse,
                     aggression::do_retargetting,
+                    aggression::release_unreachable_targets,
                     aggression::find_targets,
                 )
                     .chain(),
                 idle::idle_to_combat,
-            ),
+            )
+                .in_set(AISystems),
         );
     }
 }
@@ -1,6 +1,8 @@
 //! Implements AI for moving and steering entities.
 
 use crate::ai::idle::IdleBehavior;
+use crate::combat::damage::LastDamageTimer;
+use crate::combat::shields::{DirectionalShield, ShieldFacing};
 use crate::combat::Target;
 use crate::constants::FIXED_TIME_STEP;
 use crate::math_util::*;
@@ -88,6 +90,50 @@ pub fn pursue(
     //println!("pursue: {:?} entities, {:?} err, {:?} ok.", query.iter_mut().len(), err_count, ok_count);
 }
 
+/// Time after a shield hit during which a ship keeps turning its damaged facing away, in seconds.
+const SHIELD_FACING_MEMORY: f32 = 2.0;
+/// Fraction of a quadrant's health below which the facing is turned away from incoming fire.
+const DAMAGED_FACING_FRACTION: f32 = 0.25;
+
+/// Pursuing entities with a [DirectionalShield] turn a damaged facing away from incoming fire.
+///
+/// The strongest facing is turned toward the direction of the last hit, instead of heading straight for the target.
+pub fn turn_damaged_shield_facing_away(
+    mut query: Query<
+        (
+            &DirectionalShield,
+            &LastDamageTimer,
+            &GlobalTransform,
+            &Heading,
+            &mut TurnToDestinationBehavior,
+        ),
+        With<PursueBehavior>,
+    >,
+) {
+    for (shield, timer, transform, heading, mut turn_to) in query.iter_mut() {
+        if timer.0 > SHIELD_FACING_MEMORY {
+            continue;
+        }
+        let Some(hit_heading) = shield.last_hit_heading else {
+            continue;
+        };
+
+        let facing = ShieldFacing::from_relative_angle(get_angle_difference(hit_heading, heading.radians));
+        if shield.get(facing) >= shield.quadrant_max * DAMAGED_FACING_FRACTION {
+            continue;
+        }
+        let strongest = shield.strongest();
+        if strongest == facing {
+            continue;
+        }
+
+        let desired_heading = hit_heading - strongest.relative_angle();
+        let distance = (turn_to.destination - transform.translation()).length().max(PROXIMITY_RADIUS);
+        turn_to.destination = transform.translation()
+            + Vec3::new(desired_heading.cos(), desired_heading.sin(), 0.0) * distance;
+    }
+}
+
 /// A 'peel' manoeuvre causes an entity to move away from its target.
 /// 
 /// It is usually triggered when the entity gets too close.
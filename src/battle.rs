@@ -0,0 +1,346 @@
+//! The lifecycle of a battle, from setup until a victory condition is reached.
+//!
+//! Add the [BattlePlugin] to stop the simulation while a battle is being set up or finished. The battle starts once the
+//! first ships have spawned and nothing is left loading, and finishes when any of the [VictoryConditions] is met,
+//! sending a [BattleResult].
+
+use std::collections::BTreeMap;
+
+use bevy::{asset::LoadState, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    ai::{aggression::AgentCategory, AISystems},
+    combat::{
+        effects::{EffectSource, Effector, Instigator},
+        mortal::{Dieing, Health, Mortal},
+        CombatSystems, Team,
+    },
+    game::{GameSpeed, GameTimeDelta, SimulationTickSystems},
+    movement::MovementSystems,
+    scenario::PendingScenario,
+    templates::ships::asset::TemplateSpawner,
+};
+
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BattleState {
+    /// Ships are being spawned, and the simulation has not started. The [SimulationTick](crate::game::SimulationTick)
+    /// does not advance, so that how long templates take to load does not change the battle.
+    #[default]
+    Setup,
+    Running,
+    /// The [GameSpeed] is held at zero. The simulation keeps stepping, so that replays see the pause as a speed change.
+    Paused,
+    /// A victory condition was reached, and the simulation has stopped.
+    Finished,
+}
+
+/// A way for the battle to end.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum VictoryCondition {
+    /// The battle ends when at most one team has ships left, which wins.
+    Annihilation,
+    /// The battle ends after the given number of seconds, won by the team with the most hull health left.
+    TimeLimit(f32),
+    /// The team wins once the given number of waves have been launched and no enemy ships are left, and loses if it
+    /// has no ships left.
+    SurviveWaves { team: i32, waves: u32 },
+    /// The team loses once none of its [Vip] ships are left.
+    ProtectVip { team: i32 },
+}
+
+/// Marks a ship that must be protected, see [VictoryCondition::ProtectVip].
+#[derive(Component)]
+pub struct Vip;
+
+/// The conditions under which the battle ends. The first condition met decides the result.
+#[derive(Resource, Default, Clone)]
+pub struct VictoryConditions(pub Vec<VictoryCondition>);
+
+/// Number of waves of reinforcements launched so far.
+#[derive(Resource, Default)]
+pub struct BattleWaves {
+    pub launched: u32,
+}
+
+/// Time the battle has been running, in seconds of simulated time.
+#[derive(Resource, Default)]
+pub struct BattleClock(pub f32);
+
+/// The outcome of a finished battle. Sent as an event, and inserted as a resource.
+#[derive(Event, Resource, Clone, Copy, Debug, PartialEq)]
+pub struct BattleResult {
+    /// The winning team, or `None` for a draw.
+    pub winner: Option<i32>,
+    /// The condition that ended the battle.
+    pub condition: VictoryCondition,
+    /// Length of the battle, in seconds of simulated time.
+    pub duration: f32,
+}
+
+/// The ships a team has left.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Fleet {
+    pub ships: u32,
+    pub health: f32,
+    pub vips: u32,
+}
+
+impl VictoryCondition {
+    /// Checks whether the battle has ended, returning the winning team, or `None` for a draw.
+    pub fn check(
+        &self,
+        fleets: &BTreeMap<i32, Fleet>,
+        elapsed: f32,
+        waves: u32,
+    ) -> Option<Option<i32>> {
+        // The winner when the team loses, if only one other team is left.
+        let other_team = |team: i32| {
+            let mut others = fleets.keys().filter(|other| **other != team);
+            match (others.next(), others.next()) {
+                (Some(other), None) => Some(*other),
+                _ => None,
+            }
+        };
+
+        match *self {
+            VictoryCondition::Annihilation => match fleets.len() {
+                0 => Some(None),
+                1 => Some(fleets.keys().next().copied()),
+                _ => None,
+            },
+            VictoryCondition::TimeLimit(seconds) => {
+                if elapsed < seconds {
+                    return None;
+                }
+                let strongest = fleets
+                    .iter()
+                    .max_by(|(_, a), (_, b)| a.health.total_cmp(&b.health));
+                let tied = strongest.is_some_and(|(team, strongest)| {
+                    fleets
+                        .iter()
+                        .any(|(other, fleet)| other != team && fleet.health == strongest.health)
+                });
+                Some(strongest.filter(|_| !tied).map(|(team, _)| *team))
+            }
+            VictoryCondition::SurviveWaves {
+                team,
+                waves: target,
+            } => {
+                if !fleets.contains_key(&team) {
+                    Some(other_team(team))
+                } else if waves >= target && fleets.len() == 1 {
+                    Some(Some(team))
+                } else {
+                    None
+                }
+            }
+            VictoryCondition::ProtectVip { team } => {
+                if fleets.get(&team).map_or(0, |fleet| fleet.vips) == 0 {
+                    Some(other_team(team))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// Ships that are still fighting.
+pub type SurvivingShip = (With<Mortal>, Without<Dieing>, Without<Parent>);
+
+/// Missiles still in flight do not count as surviving ships.
+pub fn is_missile(category: &AgentCategory) -> bool {
+    category.contains(AgentCategory::MISSILE)
+}
+
+pub struct BattlePlugin;
+
+impl Plugin for BattlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<BattleState>()
+            .init_resource::<VictoryConditions>()
+            .init_resource::<BattleWaves>()
+            .init_resource::<BattleClock>()
+            .add_event::<BattleResult>();
+
+        let running = || in_state(BattleState::Running).or_else(in_state(BattleState::Paused));
+        app.configure_sets(FixedUpdate, AISystems.run_if(running()))
+            .configure_sets(FixedUpdate, MovementSystems.run_if(running()))
+            .configure_sets(FixedUpdate, CombatSystems.run_if(running()))
+            .configure_sets(
+                FixedLast,
+                SimulationTickSystems.run_if(not(in_state(BattleState::Setup))),
+            );
+
+        app.add_systems(
+            FixedPostUpdate,
+            (
+                start_battle.run_if(in_state(BattleState::Setup)),
+                check_victory_conditions.run_if(running()),
+            ),
+        );
+        app.add_systems(
+            Update,
+            toggle_pause.run_if(resource_exists::<ButtonInput<KeyCode>>),
+        );
+    }
+}
+
+/// Starts the battle once the first ships have spawned, no ship is waiting on its template and every weapon
+/// definition has loaded. A battle with a [PendingScenario] also waits for the scenario to start.
+fn start_battle(
+    pending_scenario: Option<Res<PendingScenario>>,
+    query: Query<(), SurvivingShip>,
+    pending_query: Query<(), (With<TemplateSpawner>, Without<Instigator>)>,
+    effector_query: Query<&Effector>,
+    asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<BattleState>>,
+) {
+    if pending_scenario.is_some() || query.is_empty() || !pending_query.is_empty() {
+        return;
+    }
+    let loading = effector_query.iter().any(|effector| match &effector.source {
+        EffectSource::Weapon(handle) => matches!(
+            asset_server.load_state(handle),
+            LoadState::NotLoaded | LoadState::Loading
+        ),
+        EffectSource::Spawner(_) => false,
+    });
+    if !loading {
+        next_state.set(BattleState::Running);
+    }
+}
+
+/// Pauses by setting the [GameSpeed] to zero, restoring the previous speed when unpaused.
+fn toggle_pause(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<BattleState>>,
+    mut next_state: ResMut<NextState<BattleState>>,
+    mut speed: ResMut<GameSpeed>,
+    mut paused_speed: Local<i32>,
+) {
+    let toggled = keyboard_input.just_pressed(KeyCode::KeyP);
+    match state.get() {
+        BattleState::Running if toggled => {
+            *paused_speed = speed.0;
+            speed.0 = 0;
+            next_state.set(BattleState::Paused);
+        }
+        BattleState::Paused if toggled => {
+            speed.0 = *paused_speed;
+            next_state.set(BattleState::Running);
+        }
+        // The speed was raised some other way, e.g. from the keyboard or by a replay.
+        BattleState::Paused if speed.0 != 0 => next_state.set(BattleState::Running),
+        _ => {}
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn check_victory_conditions(
+    mut commands: Commands,
+    mut events: EventWriter<BattleResult>,
+    mut next_state: ResMut<NextState<BattleState>>,
+    mut clock: ResMut<BattleClock>,
+    dt: Res<GameTimeDelta>,
+    conditions: Res<VictoryConditions>,
+    waves: Res<BattleWaves>,
+    query: Query<(&Team, &Health, &AgentCategory, Has<Vip>), SurvivingShip>,
+) {
+    clock.0 += dt.0;
+
+    let mut fleets = BTreeMap::new();
+    for (team, health, category, vip) in query.iter() {
+        if is_missile(category) {
+            continue;
+        }
+        let fleet: &mut Fleet = fleets.entry(team.0).or_default();
+        fleet.ships += 1;
+        fleet.health += health.0.max(0.0);
+        if vip {
+            fleet.vips += 1;
+        }
+    }
+
+    for condition in conditions.0.iter() {
+        let Some(winner) = condition.check(&fleets, clock.0, waves.launched) else {
+            continue;
+        };
+        let result = BattleResult {
+            winner,
+            condition: *condition,
+            duration: clock.0,
+        };
+        match winner {
+            Some(team) => info!("Team {} won the battle by {:?}", team, condition),
+            None => info!("The battle ended in a draw by {:?}", condition),
+        }
+        commands.insert_resource(result);
+        events.send(result);
+        next_state.set(BattleState::Finished);
+        return;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_victory_conditions() {
+        let fleet = |ships: u32, health: f32, vips: u32| Fleet {
+            ships,
+            health,
+            vips,
+        };
+        let fleets: BTreeMap<_, _> = [(1, fleet(3, 150.0, 0)), (2, fleet(1, 200.0, 1))].into();
+
+        assert_eq!(VictoryCondition::Annihilation.check(&fleets, 10.0, 0), None);
+        assert_eq!(
+            VictoryCondition::TimeLimit(60.0).check(&fleets, 60.0, 0),
+            Some(Some(2))
+        );
+        assert_eq!(
+            VictoryCondition::ProtectVip { team: 1 }.check(&fleets, 10.0, 0),
+            Some(Some(2))
+        );
+        let survive = VictoryCondition::SurviveWaves { team: 2, waves: 3 };
+        assert_eq!(survive.check(&fleets, 10.0, 3), None);
+
+        let fleets: BTreeMap<_, _> = [(2, fleet(1, 200.0, 1))].into();
+        assert_eq!(survive.check(&fleets, 10.0, 2), None);
+        assert_eq!(survive.check(&fleets, 10.0, 3), Some(Some(2)));
+        assert_eq!(
+            VictoryCondition::Annihilation.check(&fleets, 10.0, 0),
+            Some(Some(2))
+        );
+    }
+
+    #[test]
+    fn test_pause_holds_game_speed_at_zero() {
+        let mut app = App::new();
+        app.add_plugins(BattlePlugin)
+            .insert_resource(GameSpeed(3))
+            .init_resource::<ButtonInput<KeyCode>>();
+        app.world
+            .resource_mut::<NextState<BattleState>>()
+            .set(BattleState::Running);
+        app.update();
+
+        let press_pause = |app: &mut App| {
+            app.world
+                .resource_mut::<ButtonInput<KeyCode>>()
+                .press(KeyCode::KeyP);
+            app.update();
+            app.world.resource_mut::<ButtonInput<KeyCode>>().reset(KeyCode::KeyP);
+            app.update();
+            (
+                *app.world.resource::<State<BattleState>>().get(),
+                app.world.resource::<GameSpeed>().0,
+            )
+        };
+        assert_eq!(press_pause(&mut app), (BattleState::Paused, 0));
+        assert_eq!(press_pause(&mut app), (BattleState::Running, 3));
+    }
+}
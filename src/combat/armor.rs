@@ -0,0 +1,105 @@
+//! Armor plating, which sits between shields and the hull.
+//!
+//! Each hit that gets past the shields has its damage reduced by a flat amount, which wears down the plating. Once the
+//! plating's health is gone, hits pass straight through to the hull.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    attack::{Attack, AttackResult},
+    damage::Damage,
+    effects::{Effect, Effectiveness},
+    Target,
+};
+
+#[derive(Component, Clone, Copy, Deserialize)]
+pub struct Armor {
+    /// Damage stopped from each hit while the plating holds.
+    pub reduction: f32,
+    /// Remaining health of the plating. Damage stopped by the armor is taken from this pool.
+    pub health: f32,
+}
+
+/// Reduces the [Armor::reduction] of the target hit by an attack.
+#[derive(Component, Clone, Copy)]
+pub struct ArmorPenetration(pub f32);
+
+type ArmoredAttack<'a> = (
+    &'a mut Damage,
+    &'a Target,
+    &'a Attack,
+    Option<&'a ArmorPenetration>,
+    Option<&'a Effectiveness>,
+);
+
+/// Stops part of the damage of each hit, measured after the hit's [Effectiveness] is applied.
+pub fn armor_absorb_damage(
+    mut attacks_query: Query<ArmoredAttack, With<Effect>>,
+    mut armor_query: Query<&mut Armor>,
+) {
+    for (mut damage, target, attack, penetration, effectiveness) in attacks_query.iter_mut() {
+        if attack.result != AttackResult::Hit {
+            continue;
+        }
+        let effectiveness = effectiveness.map_or(1.0, |e| e.0);
+        if effectiveness <= 0.0 {
+            continue;
+        }
+
+        let Some(target_entity) = target.0 else {
+            continue;
+        };
+
+        if let Ok(mut armor) = armor_query.get_mut(target_entity) {
+            let reduction = armor.reduction - penetration.map_or(0.0, |p| p.0);
+            let stopped = reduction
+                .min(damage.0 * effectiveness)
+                .min(armor.health)
+                .max(0.0);
+            damage.0 -= stopped / effectiveness;
+            armor.health -= stopped;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_armor_is_penetrated_and_worn_down() {
+        let mut app = App::new();
+        app.add_systems(Update, armor_absorb_damage);
+        let ship = app
+            .world
+            .spawn(Armor {
+                reduction: 8.0,
+                health: 10.0,
+            })
+            .id();
+        let mut fire = |penetration: f32| {
+            let attack = app
+                .world
+                .spawn((
+                    Damage::new(20.0),
+                    Target(Some(ship)),
+                    Attack::new(1.0),
+                    ArmorPenetration(penetration),
+                    Effect,
+                ))
+                .id();
+            app.update();
+            let remaining = app.world.get::<Damage>(attack).unwrap().0;
+            app.world.despawn(attack);
+            (remaining, app.world.get::<Armor>(ship).unwrap().health)
+        };
+
+        // penetration beyond the reduction ignores the armor entirely
+        assert_eq!(fire(10.0), (20.0, 10.0));
+        assert_eq!(fire(5.0), (17.0, 7.0));
+        // the plating cannot stop more than its remaining health
+        assert_eq!(fire(0.0), (13.0, 0.0));
+        assert_eq!(fire(0.0), (20.0, 0.0));
+    }
+}
@@ -14,6 +14,11 @@ pub struct Attack {
     pub result: AttackResult,
 }
 
+/// Marks an attack that continues an earlier one, e.g. a blast fragment or a later tick of a beam, so that it is not
+/// counted as a separate shot.
+#[derive(Component)]
+pub struct FollowUpAttack;
+
 impl Attack {
     pub fn new(accuracy: f32) -> Self {
         Attack {
@@ -0,0 +1,151 @@
+//! Beam weapons that stay locked on to a target, applying their effect every tick until the beam ends.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game::GameTimeDelta;
+
+use super::{
+    tools::{Cooldown, TargettedTool},
+    Target,
+};
+
+/// A [TargettedTool] that holds its beam on the target once fired, applying its effector repeatedly.
+///
+/// The beam breaks early if the target leaves the range or cone of the tool, or the tool changes target. The tool's
+/// [Cooldown] only starts counting down once the beam has ended.
+#[derive(Component, Clone, Copy, Deserialize)]
+pub struct SustainedBeam {
+    /// How long the beam is held on target, in seconds.
+    pub duration: f32,
+    /// Time between applications of the effector while the beam is held, in seconds.
+    pub tick_interval: f32,
+    /// The entity the beam is locked on to, while it is firing.
+    #[serde(skip)]
+    pub locked: Option<Entity>,
+    /// Time since the beam locked on, in seconds.
+    #[serde(skip)]
+    pub elapsed: f32,
+    #[serde(skip)]
+    next_tick: f32,
+}
+
+impl SustainedBeam {
+    pub fn new(duration: f32, tick_interval: f32) -> Self {
+        SustainedBeam {
+            duration,
+            tick_interval,
+            locked: None,
+            elapsed: 0.0,
+            next_tick: 0.0,
+        }
+    }
+
+    /// True while the beam is being held on a target.
+    pub fn is_firing(&self) -> bool {
+        self.locked.is_some()
+    }
+
+    /// True while the beam is being held on a target it has already hit at least once.
+    pub fn is_ticking(&self) -> bool {
+        self.is_firing() && self.elapsed > 0.0
+    }
+}
+
+type BeamToolQuery<'a> = (
+    &'a mut SustainedBeam,
+    &'a mut TargettedTool,
+    &'a mut Cooldown,
+    &'a Target,
+    &'a GlobalTransform,
+);
+
+/// Locks beams on to their target when their tool fires, and fires the tool again on each tick of a held beam.
+pub fn sustain_beams(
+    dt: Res<GameTimeDelta>,
+    mut query: Query<BeamToolQuery>,
+    pos_query: Query<&GlobalTransform>,
+) {
+    for (mut beam, mut tool, mut cooldown, target, transform) in query.iter_mut() {
+        let Some(locked) = beam.locked else {
+            if tool.firing {
+                beam.locked = target.0;
+                beam.elapsed = 0.0;
+                beam.next_tick = beam.tick_interval;
+            }
+            continue;
+        };
+
+        beam.elapsed += dt.0;
+        let held = tool.armed
+            && beam.elapsed < beam.duration
+            && target.0 == Some(locked)
+            && pos_query.get(locked).is_ok_and(|target_transform| {
+                tool.in_reach(transform, target_transform.translation())
+            });
+        if !held {
+            beam.locked = None;
+            continue;
+        }
+
+        cooldown.reset();
+        beam.next_tick -= dt.0;
+        if beam.next_tick <= 0.0 {
+            tool.firing = true;
+            beam.next_tick += beam.tick_interval;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beam_ticks_until_target_leaves_range() {
+        let mut app = App::new();
+        app.insert_resource(GameTimeDelta(0.1))
+            .add_systems(Update, sustain_beams);
+        let target = app
+            .world
+            .spawn(GlobalTransform::from_translation(Vec3::new(0.0, 50.0, 0.0)))
+            .id();
+        let tool = app
+            .world
+            .spawn((
+                SustainedBeam::new(1.0, 0.25),
+                TargettedTool {
+                    cone: 0.5,
+                    range: 100.0,
+                    armed: true,
+                    firing: true,
+                },
+                Cooldown::new(2.0),
+                Target(Some(target)),
+                GlobalTransform::default(),
+            ))
+            .id();
+
+        let mut ticks = 0;
+        for _ in 0..5 {
+            app.update();
+            let mut tool_mut = app.world.get_mut::<TargettedTool>(tool).unwrap();
+            if tool_mut.firing {
+                ticks += 1;
+            }
+            tool_mut.firing = false;
+        }
+        assert_eq!(
+            app.world.get::<SustainedBeam>(tool).unwrap().locked,
+            Some(target)
+        );
+        // The shot that locked on, then one every 0.25 seconds.
+        assert_eq!(ticks, 2);
+
+        *app.world.get_mut::<GlobalTransform>(target).unwrap() =
+            GlobalTransform::from_translation(Vec3::new(0.0, 150.0, 0.0));
+        app.update();
+        assert!(!app.world.get::<SustainedBeam>(tool).unwrap().is_firing());
+        assert!(!app.world.get::<TargettedTool>(tool).unwrap().firing);
+    }
+}
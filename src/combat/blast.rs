@@ -0,0 +1,170 @@
+//! Explosions that damage everything nearby.
+//!
+//! When an effect with a [BlastRadius] is applied, every other entity with [Health] inside the radius is hit by a
+//! [BlastFragment] carrying a share of the effect's damage. Fragments are checked against each victim's shields and
+//! armor as normal attacks, but cannot be evaded.
+
+use bevy::{ecs::query::QueryData, prelude::*, utils::HashSet};
+use serde::Deserialize;
+
+use crate::spatial::SpatialHash;
+
+use super::{
+    armor::ArmorPenetration,
+    attack::{Attack, FollowUpAttack},
+    damage::{Damage, DamageType},
+    effects::{Effect, EffectLocation, Effectiveness, Instigator, SourceTransform},
+    mortal::Health,
+    Target, Team,
+};
+
+/// An effect that damages every entity within a radius of where it is applied.
+///
+/// Damage falls off linearly from the full amount at the centre to nothing at the edge.
+#[derive(Component, Clone, Copy, Deserialize)]
+pub struct BlastRadius(pub f32);
+
+/// Marks an effect as the part of a blast that hits one victim.
+#[derive(Component)]
+pub struct BlastFragment;
+
+/// Teams whose blasts damage their own ships.
+#[derive(Resource, Default)]
+pub struct FriendlyFire(HashSet<i32>);
+
+impl FriendlyFire {
+    pub fn enable(&mut self, team: Team) {
+        self.0.insert(team.0);
+    }
+
+    pub fn disable(&mut self, team: Team) {
+        self.0.remove(&team.0);
+    }
+
+    pub fn is_enabled(&self, team: Team) -> bool {
+        self.0.contains(&team.0)
+    }
+}
+
+#[derive(QueryData)]
+pub struct BlastQuery {
+    radius: &'static BlastRadius,
+    location: &'static EffectLocation,
+    damage: &'static Damage,
+    damage_type: Option<&'static DamageType>,
+    penetration: Option<&'static ArmorPenetration>,
+    target: &'static Target,
+    instigator: &'static Instigator,
+    team: Option<&'static Team>,
+    effectiveness: Option<&'static Effectiveness>,
+}
+
+/// Spawns a [BlastFragment] for each entity caught in a blast, other than the blast's target.
+pub fn apply_blasts(
+    mut commands: Commands,
+    blasts: Query<BlastQuery, With<Effect>>,
+    victims: Query<(Entity, &GlobalTransform, Option<&Team>), With<Health>>,
+    friendly_fire: Res<FriendlyFire>,
+) {
+    if blasts.is_empty() {
+        return;
+    }
+
+    let mut sorted_victims = SpatialHash::default();
+    for (entity, transform, team) in victims.iter() {
+        sorted_victims.insert(transform.translation(), (entity, transform.translation(), team));
+    }
+
+    for blast in blasts.iter() {
+        let centre = blast.location.0;
+        let radius = blast.radius.0;
+        for (victim, position, victim_team) in sorted_victims.near(centre, radius) {
+            if blast.target.0 == Some(*victim) {
+                continue;
+            }
+            if let (Some(team), Some(victim_team)) = (blast.team, victim_team) {
+                if team == *victim_team && !friendly_fire.is_enabled(*team) {
+                    continue;
+                }
+            }
+            let distance = (*position - centre).length();
+            if distance >= radius {
+                continue;
+            }
+
+            let mut fragment = commands.spawn((
+                Attack::new(1.0),
+                Damage::new(blast.damage.0 * (1.0 - distance / radius)),
+                blast.damage_type.copied().unwrap_or_default(),
+                Target(Some(*victim)),
+                *blast.instigator,
+                SourceTransform(GlobalTransform::from_translation(centre)),
+                EffectLocation(*position),
+                Effectiveness(blast.effectiveness.map_or(1.0, |e| e.0)),
+                Effect,
+                BlastFragment,
+                FollowUpAttack,
+            ));
+            if let Some(penetration) = blast.penetration {
+                fragment.insert(*penetration);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fragment_damage(app: &mut App) -> Vec<(Entity, f32)> {
+        let mut query = app
+            .world
+            .query_filtered::<(&Target, &Damage), With<BlastFragment>>();
+        let mut fragments: Vec<(Entity, f32)> = query
+            .iter(&app.world)
+            .map(|(target, damage)| (target.0.unwrap(), damage.0))
+            .collect();
+        fragments.sort_by_key(|(entity, _)| *entity);
+        fragments
+    }
+
+    #[test]
+    fn test_blast_damage_falls_off_and_spares_friendlies() {
+        let mut app = App::new();
+        app.init_resource::<FriendlyFire>()
+            .add_systems(Update, apply_blasts);
+        let mut spawn_victim = |x: f32, team: i32| {
+            app.world
+                .spawn((
+                    Health(10.0),
+                    GlobalTransform::from_translation(Vec3::new(x, 0.0, 0.0)),
+                    Team(team),
+                ))
+                .id()
+        };
+        let target = spawn_victim(0.0, 2);
+        let near = spawn_victim(10.0, 2);
+        let friendly = spawn_victim(-10.0, 1);
+        spawn_victim(50.0, 2);
+        let instigator = app.world.spawn_empty().id();
+        app.world.spawn((
+            BlastRadius(40.0),
+            EffectLocation(Vec3::ZERO),
+            Damage::new(20.0),
+            Target(Some(target)),
+            Instigator(instigator),
+            Team(1),
+            Effect,
+        ));
+
+        app.update();
+        assert_eq!(fragment_damage(&mut app), vec![(near, 15.0)]);
+
+        app.world.resource_mut::<FriendlyFire>().enable(Team(1));
+        app.update();
+        assert_eq!(
+            fragment_damage(&mut app),
+            vec![(near, 15.0), (near, 15.0), (friendly, 15.0)]
+        );
+    }
+}
@@ -1,8 +1,13 @@
 use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game::GameTimeDelta;
 
 use super::{
-    attack::{Attack, AttackResult},
-    effects::Effect,
+    attack::{Attack, AttackResult, FollowUpAttack},
+    effectiveness::Critical,
+    effects::{Effect, Effectiveness},
+    events::{root_instigator, CombatEvent, Instigators, LastAttacker},
     mortal::Health,
     Target,
 };
@@ -17,25 +22,150 @@ impl Damage {
     }
 }
 
+/// The type of damage dealt by an entity. Damage without a type is kinetic.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Deserialize)]
+pub enum DamageType {
+    #[default]
+    Kinetic,
+    Energy,
+    Explosive,
+    Emp,
+}
+
+/// The fraction of each type of damage that an entity ignores.
+#[derive(Component, Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub struct Resistances {
+    pub kinetic: f32,
+    pub energy: f32,
+    pub explosive: f32,
+    pub emp: f32,
+}
+
+impl Resistances {
+    pub fn get(&self, damage_type: DamageType) -> f32 {
+        match damage_type {
+            DamageType::Kinetic => self.kinetic,
+            DamageType::Energy => self.energy,
+            DamageType::Explosive => self.explosive,
+            DamageType::Emp => self.emp,
+        }
+    }
+
+    /// The fraction of damage of the given type that is taken.
+    pub fn damage_taken(&self, damage_type: DamageType) -> f32 {
+        (1.0 - self.get(damage_type)).max(0.0)
+    }
+}
+
 /// Tracks when damage was last dealt to this entity.
 #[derive(Component)]
 pub struct LastDamageTimer(pub f32);
 
-/// Applies damage effects to entities.
+pub fn update_last_damage_timers(dt: Res<GameTimeDelta>, mut query: Query<&mut LastDamageTimer>) {
+    for mut timer in query.iter_mut() {
+        timer.0 += dt.0;
+    }
+}
+
+type DamagingAttack<'a> = (
+    &'a Target,
+    &'a Damage,
+    &'a Attack,
+    Option<&'a DamageType>,
+    Option<&'a Effectiveness>,
+    Instigators<'a>,
+    Has<Critical>,
+    Has<FollowUpAttack>,
+);
+
+/// Applies damage effects to entities, scaled by their [Effectiveness] and reduced by the target's [Resistances].
+///
+/// Sends a [CombatEvent::Hit] for each. The hit that takes the target's health to zero makes its instigator the
+/// target's [LastAttacker].
 pub fn apply_damage(
-    query: Query<(&Target, &Damage, &Attack), With<Effect>>,
-    mut health_query: Query<(&mut Health, &mut LastDamageTimer)>,
+    mut commands: Commands,
+    mut events: EventWriter<CombatEvent>,
+    query: Query<DamagingAttack, With<Effect>>,
+    mut health_query: Query<(&mut Health, &mut LastDamageTimer, Option<&Resistances>)>,
+    parent_query: Query<&Parent>,
 ) {
-    for (target, damage, attack) in query.iter() {
+    for (target, damage, attack, damage_type, effectiveness, instigator, critical, follow_up) in
+        query.iter()
+    {
         if attack.result != AttackResult::Hit {
             continue;
         }
 
         if let Some(target_entity) = target.0 {
-            if let Ok((mut health, mut timer)) = health_query.get_mut(target_entity) {
-                health.0 -= damage.0;
+            if let Ok((mut health, mut timer, resistances)) = health_query.get_mut(target_entity) {
+                let damage_type = damage_type.copied().unwrap_or_default();
+                let taken = resistances.map_or(1.0, |r| r.damage_taken(damage_type));
+                let effectiveness = effectiveness.map_or(1.0, |e| e.0);
+                let dealt = damage.0 * effectiveness * taken;
+                let was_alive = health.0 > 0.0;
+                health.0 -= dealt;
                 timer.0 = 0.0;
+
+                let instigator = root_instigator(instigator, &parent_query);
+                if let (Some(instigator), true) = (instigator, was_alive && health.0 <= 0.0) {
+                    commands
+                        .entity(target_entity)
+                        .insert(LastAttacker(instigator));
+                }
+                events.send(CombatEvent::Hit {
+                    instigator,
+                    target: target_entity,
+                    damage: dealt,
+                    critical,
+                    follow_up,
+                });
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resistances_reduce_damage_by_type() {
+        let mut app = App::new();
+        app.add_event::<CombatEvent>()
+            .add_systems(Update, apply_damage);
+        let ship = app
+            .world
+            .spawn((
+                Health(100.0),
+                LastDamageTimer(0.0),
+                Resistances {
+                    energy: 0.25,
+                    explosive: 1.5,
+                    ..default()
+                },
+            ))
+            .id();
+        let mut fire = |damage_type: Option<DamageType>| {
+            let mut attack = app.world.spawn((
+                Damage::new(20.0),
+                Attack::new(1.0),
+                Target(Some(ship)),
+                Effect,
+            ));
+            if let Some(damage_type) = damage_type {
+                attack.insert(damage_type);
+            }
+            let attack = attack.id();
+            app.update();
+            app.world.despawn(attack);
+            app.world.get::<Health>(ship).unwrap().0
+        };
+
+        assert_eq!(fire(Some(DamageType::Energy)), 85.0);
+        // resistances above one cannot heal
+        assert_eq!(fire(Some(DamageType::Explosive)), 85.0);
+        // damage without a type is kinetic
+        assert_eq!(fire(None), 65.0);
+    }
+}
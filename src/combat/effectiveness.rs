@@ -0,0 +1,225 @@
+//! Modifiers that scale the [Effectiveness] of attacks, so that a single number captures how well an attack landed.
+//!
+//! Modifiers are applied once an attack is known to hit. Shields, armor and damage then scale the attack's damage by
+//! its effectiveness.
+
+use bevy::{ecs::query::QueryData, prelude::*};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{ai::aggression::AgentCategory, game::rng::GameplayRng};
+
+use super::{
+    attack::{Attack, AttackResult},
+    effects::{Effect, EffectLocation, Effectiveness, Instigator, SourceTransform},
+    Target,
+};
+
+/// Chance for an attack to land a critical hit, multiplying its effectiveness.
+#[derive(Component, Clone, Copy, Deserialize)]
+pub struct CriticalHit {
+    pub chance: f32,
+    pub multiplier: f32,
+}
+
+/// Marks an attack that landed a critical hit.
+#[derive(Component)]
+pub struct Critical;
+
+/// Attacks lose effectiveness beyond their optimal range, falling linearly to the minimum at the maximum range.
+#[derive(Component, Clone, Copy, Deserialize)]
+pub struct RangeFalloff {
+    pub optimal_range: f32,
+    pub max_range: f32,
+    pub min_effectiveness: f32,
+}
+
+impl RangeFalloff {
+    pub fn effectiveness(&self, distance: f32) -> f32 {
+        if distance <= self.optimal_range {
+            return 1.0;
+        }
+        let span = (self.max_range - self.optimal_range).max(f32::EPSILON);
+        let fraction = ((distance - self.optimal_range) / span).min(1.0);
+        1.0 - fraction * (1.0 - self.min_effectiveness)
+    }
+}
+
+/// Multiplies the effectiveness of attacks against targets in any of the given categories.
+#[derive(Component, Clone, Copy, Deserialize)]
+pub struct CategoryBonus {
+    pub categories: AgentCategory,
+    pub multiplier: f32,
+}
+
+/// Multiplies the effectiveness of every effect instigated by this entity, or by its children.
+#[derive(Component, Clone, Copy)]
+pub struct EffectivenessBuff(pub f32);
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct ModifiedAttack {
+    entity: Entity,
+    effectiveness: &'static mut Effectiveness,
+    attack: &'static Attack,
+    target: &'static Target,
+    instigator: Option<&'static Instigator>,
+    source: Option<&'static SourceTransform>,
+    location: Option<&'static EffectLocation>,
+    critical: Option<&'static CriticalHit>,
+    falloff: Option<&'static RangeFalloff>,
+    bonus: Option<&'static CategoryBonus>,
+}
+
+/// Feeds each modifier of an attack that hit into its [Effectiveness].
+pub fn apply_effectiveness_modifiers(
+    mut commands: Commands,
+    mut query: Query<ModifiedAttack, With<Effect>>,
+    category_query: Query<&AgentCategory>,
+    buff_query: Query<&EffectivenessBuff>,
+    parent_query: Query<&Parent>,
+    mut rng: ResMut<GameplayRng>,
+) {
+    for mut attack in query.iter_mut() {
+        if attack.attack.result != AttackResult::Hit {
+            continue;
+        }
+        let mut effectiveness = attack.effectiveness.0;
+
+        if let Some(critical) = attack.critical {
+            if rng.gen_range(0.0..1.0) < critical.chance {
+                effectiveness *= critical.multiplier;
+                commands.entity(attack.entity).insert(Critical);
+            }
+        }
+
+        if let (Some(falloff), Some(source), Some(location)) =
+            (attack.falloff, attack.source, attack.location)
+        {
+            let distance = (location.0 - source.0.translation()).length();
+            effectiveness *= falloff.effectiveness(distance);
+        }
+
+        if let Some(bonus) = attack.bonus {
+            let category = attack
+                .target
+                .0
+                .and_then(|target| category_query.get(target).ok());
+            if category.is_some_and(|category| bonus.categories.intersects(*category)) {
+                effectiveness *= bonus.multiplier;
+            }
+        }
+
+        if let Some(instigator) = attack.instigator {
+            let parent = parent_query
+                .get(instigator.0)
+                .ok()
+                .map(|parent| parent.get());
+            for buffed in std::iter::once(instigator.0).chain(parent) {
+                if let Ok(buff) = buff_query.get(buffed) {
+                    effectiveness *= buff.0;
+                }
+            }
+        }
+
+        attack.effectiveness.0 = effectiveness.max(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::{
+        damage::{apply_damage, Damage, LastDamageTimer},
+        events::CombatEvent,
+        mortal::Health,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn test_modifiers_change_damage_dealt() {
+        let mut app = App::new();
+        app.insert_resource(GameplayRng(ChaCha8Rng::seed_from_u64(0)))
+            .add_event::<CombatEvent>()
+            .add_systems(Update, (apply_effectiveness_modifiers, apply_damage).chain());
+        let frigate = app
+            .world
+            .spawn((Health(100.0), LastDamageTimer(0.0), AgentCategory::FRIGATE))
+            .id();
+        let ship = app.world.spawn(EffectivenessBuff(1.5)).id();
+        let hardpoint = app.world.spawn_empty().set_parent(ship).id();
+        let mut fire = |modifier: &dyn Fn(&mut EntityWorldMut)| {
+            let before = app.world.get::<Health>(frigate).unwrap().0;
+            let mut attack = app.world.spawn((
+                Damage::new(10.0),
+                Attack::new(1.0),
+                Target(Some(frigate)),
+                Effectiveness::default(),
+                Effect,
+            ));
+            modifier(&mut attack);
+            let attack = attack.id();
+            app.update();
+            app.world.despawn(attack);
+            before - app.world.get::<Health>(frigate).unwrap().0
+        };
+
+        assert_eq!(fire(&|_| {}), 10.0);
+        assert_eq!(
+            fire(&|attack| {
+                attack.insert(CriticalHit {
+                    chance: 1.0,
+                    multiplier: 2.0,
+                });
+            }),
+            20.0
+        );
+        assert_eq!(
+            fire(&|attack| {
+                attack.insert(CriticalHit {
+                    chance: 0.0,
+                    multiplier: 2.0,
+                });
+            }),
+            10.0
+        );
+        assert_eq!(
+            fire(&|attack| {
+                attack.insert(CategoryBonus {
+                    categories: AgentCategory::FRIGATE | AgentCategory::CRUISER,
+                    multiplier: 2.5,
+                });
+            }),
+            25.0
+        );
+        assert_eq!(
+            fire(&|attack| {
+                attack.insert(CategoryBonus {
+                    categories: AgentCategory::FIGHTER,
+                    multiplier: 2.5,
+                });
+            }),
+            10.0
+        );
+        // the buff on the ship applies to attacks from its hardpoints
+        assert_eq!(
+            fire(&|attack| {
+                attack.insert(Instigator(hardpoint));
+            }),
+            15.0
+        );
+    }
+
+    #[test]
+    fn test_range_falloff() {
+        let falloff = RangeFalloff {
+            optimal_range: 100.0,
+            max_range: 200.0,
+            min_effectiveness: 0.5,
+        };
+        assert_eq!(falloff.effectiveness(50.0), 1.0);
+        assert_eq!(falloff.effectiveness(150.0), 0.75);
+        assert_eq!(falloff.effectiveness(300.0), 0.5);
+    }
+}
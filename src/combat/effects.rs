@@ -2,7 +2,13 @@
 
 use bevy::prelude::*;
 
-use super::Target;
+use super::{
+    attack::FollowUpAttack,
+    beams::SustainedBeam,
+    events::{root_instigator, RootInstigator},
+    Target, Team,
+};
+use crate::templates::weapons::asset::WeaponDefinition;
 
 /// Transform of the effect source.
 #[derive(Component)]
@@ -26,18 +32,34 @@ impl Default for Effectiveness {
     }
 }
 
-type Spawner = fn(&mut Commands) -> Entity;
+pub type Spawner = fn(&mut Commands) -> Entity;
+
+/// The effect spawned each time an [Effector] is applied.
+#[derive(Clone)]
+pub enum EffectSource {
+    /// The effect is spawned by a function.
+    Spawner(Spawner),
+    /// The effect is the attack described by a [WeaponDefinition] asset.
+    Weapon(Handle<WeaponDefinition>),
+}
 
 #[derive(Component)]
 pub struct Effector {
-    pub spawn_effect: Spawner,
+    pub source: EffectSource,
     /// Number of applications to apply this simulation step.
     pub number_to_apply: u16,
 }
 impl Effector {
     pub fn new(spawn_effect: Spawner) -> Self {
         Effector {
-            spawn_effect,
+            source: EffectSource::Spawner(spawn_effect),
+            number_to_apply: 0,
+        }
+    }
+
+    pub fn from_weapon(weapon: Handle<WeaponDefinition>) -> Self {
+        Effector {
+            source: EffectSource::Weapon(weapon),
             number_to_apply: 0,
         }
     }
@@ -47,18 +69,27 @@ impl Effector {
 #[derive(Component)]
 pub struct Effect;
 
+type EffectorQuery<'a> = (
+    Entity,
+    &'a Target,
+    &'a GlobalTransform,
+    &'a mut Effector,
+    Option<&'a Instigator>,
+    Option<&'a RootInstigator>,
+    Option<&'a Team>,
+    Option<&'a SustainedBeam>,
+);
+
 pub fn apply_effects(
     mut commands: Commands,
-    mut query: Query<(
-        Entity,
-        &Target,
-        &GlobalTransform,
-        &mut Effector,
-        Option<&Instigator>,
-    )>,
+    mut query: Query<EffectorQuery>,
     pos_query: Query<&GlobalTransform>,
+    parent_query: Query<&Parent>,
+    weapons: Option<Res<Assets<WeaponDefinition>>>,
 ) {
-    for (entity, target, transform, mut effect, instigator_opt) in query.iter_mut() {
+    for (entity, target, transform, mut effect, instigator_opt, root_opt, team_opt, beam) in
+        query.iter_mut()
+    {
         let Some(target_entity) = target.0 else {
             continue;
         };
@@ -73,10 +104,23 @@ pub fn apply_effects(
             None => Instigator(entity),
             Some(source_instigator) => *source_instigator,
         };
+        // The ship is captured now, so that it is still credited if it is destroyed before the effect lands.
+        let root = root_instigator((Some(&instigator), root_opt), &parent_query).map(RootInstigator);
 
         while effect.number_to_apply > 0 {
-            // Spawn the effect
-            let spawned = (effect.spawn_effect)(&mut commands);
+            // Spawn the effect. Weapons that have not loaded yet do nothing.
+            let spawned = match &effect.source {
+                EffectSource::Spawner(spawn_effect) => spawn_effect(&mut commands),
+                EffectSource::Weapon(handle) => {
+                    match weapons.as_ref().and_then(|weapons| weapons.get(handle)) {
+                        Some(weapon) => weapon.spawn_attack(&mut commands),
+                        None => {
+                            effect.number_to_apply = 0;
+                            break;
+                        }
+                    }
+                }
+            };
             commands.entity(spawned).insert((
                 Target(Some(target_entity)),
                 instigator,
@@ -87,6 +131,16 @@ pub fn apply_effects(
                 Effect,
             ));
 
+            if let Some(team) = team_opt {
+                commands.entity(spawned).insert(*team);
+            }
+            if let Some(root) = root {
+                commands.entity(spawned).insert(root);
+            }
+            // only the first tick of a beam counts as a new shot
+            if beam.is_some_and(SustainedBeam::is_ticking) {
+                commands.entity(spawned).insert(FollowUpAttack);
+            }
             if let Ok(target_transform) = pos_query.get(target_entity) {
                 commands
                     .entity(spawned)
@@ -4,10 +4,14 @@ use bevy::prelude::*;
 use rand::Rng;
 
 use super::{
-    attack::{Attack, AttackResult},
+    attack::{Attack, AttackResult, FollowUpAttack},
+    events::{root_instigator, CombatEvent, Instigators},
     Target,
 };
-use crate::movement::{MaxTurnSpeed, Speed, TurnSpeed};
+use crate::{
+    game::rng::GameplayRng,
+    movement::{MaxTurnSpeed, Speed, TurnSpeed},
+};
 
 /// An entity can evade incoming attacks.
 #[derive(Component)]
@@ -30,6 +34,10 @@ impl Evasion {
     }
 }
 
+/// Flag component that indicates an attack cannot be evaded, e.g. a shell that has already struck its target.
+#[derive(Component)]
+pub struct Unavoidable;
+
 pub const TURN_EVASION_FACTOR: f32 = 2.0;
 pub const SPEED_EVASION_FACTOR: f32 = 200.0;
 
@@ -46,14 +54,22 @@ pub fn calculate_evasion_ratings(
     }
 }
 
-/// Calculate whether attacks are hit or miss.
+type AvoidableAttack<'a> = (
+    &'a mut Attack,
+    &'a Target,
+    Instigators<'a>,
+    Has<FollowUpAttack>,
+);
+
+/// Calculate whether attacks are hit or miss, sending a [CombatEvent::Missed] for each miss.
 pub fn determine_missed_attacks(
-    mut attack_query: Query<(&mut Attack, &Target)>,
+    mut events: EventWriter<CombatEvent>,
+    mut attack_query: Query<AvoidableAttack, Without<Unavoidable>>,
     target_query: Query<&Evasion>,
+    parent_query: Query<&Parent>,
+    mut rng: ResMut<GameplayRng>,
 ) {
-    let mut rng = rand::thread_rng();
-
-    for (mut attack, target) in attack_query.iter_mut() {
+    for (mut attack, target, instigator, follow_up) in attack_query.iter_mut() {
         if target.0.is_none() {
             continue;
         }
@@ -62,6 +78,11 @@ pub fn determine_missed_attacks(
             let hit_chance = (-evasion.total / attack.accuracy).exp();
             if rng.gen_range(0.0..1.0) > hit_chance {
                 attack.result = AttackResult::Miss;
+                events.send(CombatEvent::Missed {
+                    instigator: root_instigator(instigator, &parent_query),
+                    target: target.0.expect("target is none"),
+                    follow_up,
+                });
             }
         }
     }
@@ -0,0 +1,176 @@
+//! Events describing what happens during combat, for gameplay, scoring and logging code to subscribe to.
+
+use bevy::prelude::*;
+
+use super::effects::Instigator;
+
+/// Something that happened during combat.
+///
+/// Each event names the root instigator responsible, i.e. the top-level ship rather than the hardpoint or projectile
+/// that carried the attack, where there is one.
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub enum CombatEvent {
+    /// An attack hit the target's hull, dealing the given damage.
+    Hit {
+        instigator: Option<Entity>,
+        target: Entity,
+        damage: f32,
+        critical: bool,
+        /// The attack was a [FollowUpAttack](super::attack::FollowUpAttack), rather than a new shot.
+        follow_up: bool,
+    },
+    /// An attack was evaded by the target.
+    Missed {
+        instigator: Option<Entity>,
+        target: Entity,
+        follow_up: bool,
+    },
+    /// An attack was blocked by the target's shields, which absorbed the given damage.
+    Blocked {
+        instigator: Option<Entity>,
+        target: Entity,
+        absorbed: f32,
+        follow_up: bool,
+    },
+    /// The entity started dieing. The instigator is whoever last damaged it.
+    Killed {
+        instigator: Option<Entity>,
+        entity: Entity,
+    },
+    /// A ship was spawned, e.g. launched by a carrier or placed at the start of a battle.
+    Spawned {
+        instigator: Option<Entity>,
+        entity: Entity,
+    },
+}
+
+/// The root instigator of the damage that took this entity's health to zero, which is credited with the kill.
+#[derive(Component, Clone, Copy)]
+pub struct LastAttacker(pub Entity);
+
+/// The top-level entity responsible for an effect or projectile, captured when it is spawned.
+///
+/// Unlike walking up from the [Instigator], this still names the ship that fired after it has been despawned.
+#[derive(Component, Clone, Copy)]
+pub struct RootInstigator(pub Entity);
+
+/// The [Instigator] of an entity, and its [RootInstigator] if one was captured.
+pub type Instigators<'a> = (Option<&'a Instigator>, Option<&'a RootInstigator>);
+
+/// Resolves an [Instigator] to the top-level entity it belongs to, e.g. the ship carrying the hardpoint that fired.
+///
+/// A captured [RootInstigator] is used as is.
+pub fn root_instigator(
+    (instigator, root): Instigators,
+    parent_query: &Query<&Parent>,
+) -> Option<Entity> {
+    if let Some(root) = root {
+        return Some(root.0);
+    }
+    let mut root = instigator?.0;
+    while let Ok(parent) = parent_query.get(root) {
+        root = parent.get();
+    }
+    Some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        combat::{
+            attack::Attack,
+            damage::{apply_damage, Damage, LastDamageTimer},
+            effects::Effect,
+            mortal::{check_for_dieing_entities, Health, Mortal},
+            Target,
+        },
+        game::rng::{GameplayRng, SimulationSeed},
+    };
+
+    #[test]
+    fn test_kill_is_credited_to_root_instigator() {
+        let mut app = App::new();
+        app.add_event::<CombatEvent>()
+            .insert_resource(GameplayRng::from_seed(SimulationSeed(0)))
+            .add_systems(Update, (apply_damage, check_for_dieing_entities).chain());
+        let hardpoint = app.world.spawn_empty().id();
+        let ship = app.world.spawn_empty().push_children(&[hardpoint]).id();
+        let target = app
+            .world
+            .spawn((Health(10.0), LastDamageTimer(0.0), Mortal))
+            .id();
+        app.world.spawn((
+            Damage::new(15.0),
+            Attack::new(1.0),
+            Target(Some(target)),
+            Instigator(hardpoint),
+            Effect,
+        ));
+
+        app.update();
+        let events = app.world.resource::<Events<CombatEvent>>();
+        let sent: Vec<_> = events.get_reader().read(events).copied().collect();
+        assert_eq!(
+            sent,
+            vec![
+                CombatEvent::Hit {
+                    instigator: Some(ship),
+                    target,
+                    damage: 15.0,
+                    critical: false,
+                    follow_up: false,
+                },
+                CombatEvent::Killed {
+                    instigator: Some(ship),
+                    entity: target,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_kill_is_credited_to_the_killing_blow() {
+        let mut app = App::new();
+        app.add_event::<CombatEvent>()
+            .insert_resource(GameplayRng::from_seed(SimulationSeed(0)))
+            .add_systems(Update, (apply_damage, check_for_dieing_entities).chain());
+        // the ship that fired has been destroyed, leaving its hardpoint behind
+        let destroyed_ship = app.world.spawn_empty().id();
+        app.world.despawn(destroyed_ship);
+        let hardpoint = app.world.spawn_empty().id();
+        let other_ship = app.world.spawn_empty().id();
+        let target = app
+            .world
+            .spawn((Health(10.0), LastDamageTimer(0.0), Mortal))
+            .id();
+        app.world.spawn((
+            Damage::new(15.0),
+            Attack::new(1.0),
+            Target(Some(target)),
+            Instigator(hardpoint),
+            RootInstigator(destroyed_ship),
+            Effect,
+        ));
+        // lands after the target is already dead
+        app.world.spawn((
+            Damage::new(5.0),
+            Attack::new(1.0),
+            Target(Some(target)),
+            Instigator(other_ship),
+            Effect,
+        ));
+
+        app.update();
+        let events = app.world.resource::<Events<CombatEvent>>();
+        let kills: Vec<_> = events
+            .get_reader()
+            .read(events)
+            .filter_map(|event| match event {
+                CombatEvent::Killed { instigator, .. } => Some(*instigator),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(kills, vec![Some(destroyed_ship)]);
+    }
+}
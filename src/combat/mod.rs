@@ -1,15 +1,25 @@
 use bevy::prelude::*;
 
+pub mod armor;
 pub mod attack;
+pub mod beams;
+pub mod blast;
 pub mod damage;
+pub mod effectiveness;
 pub mod effects;
+pub mod events;
 pub mod evasion;
 pub mod lifetime;
 pub mod mortal;
 pub mod projectile;
+pub mod repair;
 pub mod shields;
+pub mod stats;
+pub mod status;
+pub mod subsystems;
 pub mod targets;
 pub mod tools;
+pub mod turrets;
 
 pub use targets::Target;
 
@@ -30,12 +40,23 @@ impl Plugin for CombatPlugin {
             (
                 tools::update_cooldowns,
                 targets::copy_targets_from_parents,
+                targets::copy_teams_from_parents,
+                turrets::rotate_turrets
+                    .after(targets::copy_targets_from_parents)
+                    .before(tools::fire_targetted_tools),
                 (
                     (
-                        (tools::fire_targetted_tools, tools::tools_activate_effectors).chain(),
+                        (
+                            tools::fire_targetted_tools,
+                            beams::sustain_beams,
+                            tools::tools_activate_effectors,
+                        )
+                            .chain(),
                         (
                             projectile::initialise_projectiles,
+                            projectile::initialise_ballistic_projectiles,
                             projectile::check_projectiles_reached_target,
+                            projectile::check_ballistic_collisions,
                             projectile::update_homing_projectile_position_target,
                             projectile::projectiles_apply_effects,
                         )
@@ -45,8 +66,14 @@ impl Plugin for CombatPlugin {
                         (effects::apply_effects, evasion::calculate_evasion_ratings),
                         (
                             evasion::determine_missed_attacks,
+                            effectiveness::apply_effectiveness_modifiers,
+                            blast::apply_blasts,
                             shields::shield_absorb_damage,
+                            subsystems::locate_hull_hits,
+                            armor::armor_absorb_damage,
+                            subsystems::damage_subsystems,
                             damage::apply_damage,
+                            repair::apply_repairs,
                         )
                             .chain(),
                     )
@@ -54,14 +81,39 @@ impl Plugin for CombatPlugin {
                     projectile::despawn_projectiles,
                 )
                     .chain(),
+                subsystems::destroy_subsystems.after(subsystems::damage_subsystems),
+                (
+                    damage::update_last_damage_timers,
+                    shields::collapse_shields,
+                    shields::reboot_shields,
+                    shields::regenerate_shields,
+                )
+                    .chain()
+                    .after(damage::apply_damage),
                 mortal::update_dieing,
-                mortal::check_for_dieing_entities,
-                lifetime::update_lifetimes,
+                mortal::check_for_dieing_entities
+                    .after(damage::apply_damage)
+                    .after(status::update_statuses),
+                (
+                    lifetime::update_lifetimes,
+                    status::end_expired_statuses,
+                    status::inflict_statuses,
+                    status::update_statuses,
+                )
+                    .chain()
+                    .after(damage::apply_damage),
                 // effects::remove_old_effects
             )
                 .in_set(CombatSystems),
         );
-        app.add_systems(FixedPostUpdate, effects::remove_old_effects);
+        app.add_event::<shields::ShieldEvent>()
+            .add_event::<events::CombatEvent>()
+            .init_resource::<blast::FriendlyFire>()
+            .init_resource::<stats::BattleStats>();
+        app.add_systems(
+            FixedPostUpdate,
+            (effects::remove_old_effects, stats::record_combat_stats),
+        );
         app.add_systems(
             PostUpdate,
             (apply_deferred, mortal::dispose_dieing, apply_deferred).chain(),
@@ -3,7 +3,9 @@
 use bevy::prelude::*;
 use rand::Rng;
 
-use crate::game::GameTimeDelta;
+use crate::game::{rng::GameplayRng, GameTimeDelta};
+
+use super::events::{CombatEvent, LastAttacker};
 
 #[derive(Component)]
 pub struct Health(pub f32);
@@ -24,12 +26,16 @@ pub struct Dieing {
     pub dispose: bool,
 }
 
+type LivingEntity<'a> = (Entity, &'a Health, Option<&'a LastAttacker>);
+
+/// Starts entities without health dieing, sending a [CombatEvent::Killed] crediting their [LastAttacker].
 pub fn check_for_dieing_entities(
     mut commands: Commands,
-    query: Query<(Entity, &Health), (With<Mortal>, Without<Dieing>)>,
+    mut events: EventWriter<CombatEvent>,
+    query: Query<LivingEntity, (With<Mortal>, Without<Dieing>)>,
+    mut rng: ResMut<GameplayRng>,
 ) {
-    let mut rng = rand::thread_rng();
-    for (entity, health) in query.iter() {
+    for (entity, health, last_attacker) in query.iter() {
         if health.0 <= 0.0 {
             // There's a chance things die instantly.
             let time = if rng.gen_range(0.0..1.0) < 0.3 {
@@ -43,6 +49,10 @@ pub fn check_for_dieing_entities(
                 dead: false,
                 dispose: false,
             });
+            events.send(CombatEvent::Killed {
+                instigator: last_attacker.map(|attacker| attacker.0),
+                entity,
+            });
         }
     }
 }
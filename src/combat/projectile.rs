@@ -1,9 +1,16 @@
-use bevy::prelude::*;
+use bevy::{ecs::query::QueryData, prelude::*};
 
-use crate::{ai::movement::TurnToDestinationBehavior, movement::TurnSpeed};
+use crate::{
+    ai::movement::TurnToDestinationBehavior,
+    game::GameTimeDelta,
+    math_util::{get_angle_difference, get_heading_to_point, get_intercept_point},
+    movement::{Heading, Speed, TurnSpeed, Velocity},
+    spatial::SpatialHash,
+};
 
 use super::{
     effects::{Effector, Instigator},
+    events::{root_instigator, Instigators, RootInstigator},
     Target, Team,
 };
 
@@ -33,6 +40,37 @@ pub struct CircularHitBox {
 #[derive(Component, Default, Copy, Clone)]
 pub struct Homing;
 
+/// An unguided projectile that flies in a straight line.
+///
+/// It hits the first hostile [CircularHitBox] in its path, which need not be its [Target].
+#[derive(Component, Copy, Clone)]
+pub struct Ballistic {
+    /// Speed the projectile is fired at.
+    pub speed: f32,
+    /// Radius of the projectile, added to the radius of hit boxes it passes.
+    pub radius: f32,
+}
+
+/// Steers a [Homing] projectile by proportional navigation.
+///
+/// The projectile turns by `gain` times the change in the line of sight to its target, which leads a moving target
+/// instead of chasing it.
+#[derive(Component, Copy, Clone)]
+pub struct ProportionalNavigation {
+    pub gain: f32,
+    /// Heading of the line of sight to the target on the previous step.
+    pub line_of_sight: Option<f32>,
+}
+
+impl ProportionalNavigation {
+    pub fn new(gain: f32) -> Self {
+        ProportionalNavigation {
+            gain,
+            line_of_sight: None,
+        }
+    }
+}
+
 // A homing missle:
 // - Projectile
 // - Target
@@ -43,40 +81,82 @@ pub struct Homing;
 // - TurnToDestinationBehavior
 // - Homing
 
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct HomingProjectile {
+    entity: Entity,
+    turn_to_destination: &'static mut TurnToDestinationBehavior,
+    target: &'static Target,
+    turn_speed: &'static mut TurnSpeed,
+    transform: &'static GlobalTransform,
+    speed: Option<&'static Speed>,
+    heading: Option<&'static Heading>,
+    navigation: Option<&'static mut ProportionalNavigation>,
+}
+
+/// Steers homing projectiles toward the point where they will meet their target.
+///
+/// Projectiles with [ProportionalNavigation] use it once they are facing their target.
 pub fn update_homing_projectile_position_target(
     mut commands: Commands,
-    mut query: Query<
-        (
-            Entity,
-            &mut TurnToDestinationBehavior,
-            &Target,
-            &mut TurnSpeed,
-        ),
-        (With<Homing>, With<Projectile>),
-    >,
-    transforms: Query<&GlobalTransform>,
+    mut query: Query<HomingProjectile, (With<Homing>, With<Projectile>)>,
+    targets: Query<(&GlobalTransform, Option<&Velocity>)>,
 ) {
-    for (entity, mut turn_to_destination, target, mut turn_speed) in query.iter_mut() {
-        if let Some(target_entity) = target.0 {
-            if let Ok(target_transform) = transforms.get(target_entity) {
-                turn_to_destination.destination = target_transform.translation();
-            } else {
-                commands
-                    .entity(entity)
-                    .remove::<TurnToDestinationBehavior>();
-                turn_speed.radians_per_second = 0.0;
-            }
-        } else {
+    for mut projectile in query.iter_mut() {
+        let Some(Ok((target_transform, target_velocity))) =
+            projectile.target.0.map(|target| targets.get(target))
+        else {
             commands
-                .entity(entity)
+                .entity(projectile.entity)
                 .remove::<TurnToDestinationBehavior>();
-            turn_speed.radians_per_second = 0.0;
-        }
+            projectile.turn_speed.radians_per_second = 0.0;
+            continue;
+        };
+
+        let position = projectile.transform.translation();
+        let target_position = target_transform.translation();
+        let line_of_sight = get_heading_to_point(target_position - position);
+
+        let previous_line_of_sight = projectile
+            .navigation
+            .as_mut()
+            .and_then(|navigation| navigation.line_of_sight.replace(line_of_sight));
+        let navigated_heading = match (
+            &projectile.navigation,
+            projectile.heading,
+            previous_line_of_sight,
+        ) {
+            (Some(navigation), Some(heading), Some(previous))
+                if get_angle_difference(line_of_sight, heading.radians).abs()
+                    < std::f32::consts::FRAC_PI_2 =>
+            {
+                Some(
+                    heading.radians
+                        + navigation.gain * get_angle_difference(line_of_sight, previous),
+                )
+            }
+            _ => None,
+        };
+
+        projectile.turn_to_destination.destination = match navigated_heading {
+            Some(heading) => {
+                position
+                    + Vec3::new(heading.cos(), heading.sin(), 0.0)
+                        * (target_position - position).length()
+            }
+            None => match (projectile.speed, target_velocity) {
+                (Some(speed), Some(velocity)) => {
+                    get_intercept_point(position, target_position, velocity.0, speed.0)
+                        .unwrap_or(target_position)
+                }
+                _ => target_position,
+            },
+        };
     }
 }
 
 pub fn check_projectiles_reached_target(
-    mut query: Query<(&Target, &GlobalTransform, &mut Projectile)>,
+    mut query: Query<(&Target, &GlobalTransform, &mut Projectile), Without<Ballistic>>,
     transforms: Query<(&GlobalTransform, &CircularHitBox)>,
 ) {
     for (target, transform, mut projectile) in query.iter_mut() {
@@ -96,6 +176,122 @@ pub fn check_projectiles_reached_target(
     }
 }
 
+/// Fraction of the way along a path at which a circle moving along it first touches another circle.
+///
+/// # Arguments
+///
+/// * `start`: position of the moving circle at the start of the path.
+/// * `path`: displacement of the moving circle over the path.
+/// * `centre`: position of the stationary circle.
+/// * `radius`: sum of the radii of the two circles.
+pub fn swept_circle_entry(start: Vec3, path: Vec3, centre: Vec3, radius: f32) -> Option<f32> {
+    let offset = start - centre;
+    let c = offset.length_squared() - radius.powi(2);
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+    let a = path.length_squared();
+    if a == 0.0 {
+        return None;
+    }
+    let b = offset.dot(path);
+    let discriminant = b * b - a * c;
+    if b >= 0.0 || discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / a;
+    if t <= 1.0 {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+struct HitBoxInformation {
+    entity: Entity,
+    position: Vec3,
+    radius: f32,
+    team: Option<Team>,
+}
+
+type BallisticQuery<'a> = (
+    &'a Ballistic,
+    &'a Velocity,
+    &'a GlobalTransform,
+    Option<&'a Team>,
+    Instigators<'a>,
+    &'a mut Target,
+    &'a mut Projectile,
+);
+
+/// Sweeps ballistic projectiles along their path for this step, targeting the first hostile hit box they cross.
+///
+/// Without teams to compare, every hit box is hostile except the ship that fired the projectile.
+pub fn check_ballistic_collisions(
+    dt: Res<GameTimeDelta>,
+    mut query: Query<BallisticQuery>,
+    hit_boxes: Query<(Entity, &GlobalTransform, &CircularHitBox, Option<&Team>)>,
+    parent_query: Query<&Parent>,
+) {
+    if query.is_empty() {
+        return;
+    }
+
+    let mut sorted_hit_boxes = SpatialHash::default();
+    let mut max_radius: f32 = 0.0;
+    for (entity, transform, hit_box, team) in hit_boxes.iter() {
+        max_radius = max_radius.max(hit_box.radius);
+        sorted_hit_boxes.insert(
+            transform.translation(),
+            HitBoxInformation {
+                entity,
+                position: transform.translation(),
+                radius: hit_box.radius,
+                team: team.copied(),
+            },
+        );
+    }
+
+    for (ballistic, velocity, transform, team, instigator, mut target, mut projectile) in
+        query.iter_mut()
+    {
+        if projectile.reached_target {
+            continue;
+        }
+        let start = transform.translation();
+        let path = velocity.0 * dt.0;
+        let search_radius = 0.5 * path.length() + max_radius + ballistic.radius;
+        let firing_ship = root_instigator(instigator, &parent_query);
+
+        let mut first_hit: Option<(f32, Entity)> = None;
+        for candidate in sorted_hit_boxes.near(start + 0.5 * path, search_radius) {
+            let hostile = match (team, candidate.team) {
+                (Some(team), Some(candidate_team)) => *team != candidate_team,
+                _ => firing_ship != Some(candidate.entity),
+            };
+            if !hostile {
+                continue;
+            }
+            let Some(t) = swept_circle_entry(
+                start,
+                path,
+                candidate.position,
+                candidate.radius + ballistic.radius,
+            ) else {
+                continue;
+            };
+            if first_hit.is_none_or(|(first_t, _)| t < first_t) {
+                first_hit = Some((t, candidate.entity));
+            }
+        }
+
+        if let Some((_, entity)) = first_hit {
+            target.0 = Some(entity);
+            projectile.reached_target = true;
+        }
+    }
+}
+
 pub fn projectiles_apply_effects(mut query: Query<(&mut Effector, &Projectile)>) {
     for (mut effector, projectile) in query.iter_mut() {
         if projectile.reached_target {
@@ -112,19 +308,55 @@ pub fn despawn_projectiles(mut commands: Commands, mut query: Query<(Entity, &Pr
     }
 }
 
+/// Launches ballistic projectiles along the direction they face.
+pub fn initialise_ballistic_projectiles(
+    mut query: Query<(&Ballistic, &Transform, &mut Velocity), Added<Ballistic>>,
+) {
+    for (ballistic, transform, mut velocity) in query.iter_mut() {
+        velocity.0 = ballistic.speed * *transform.local_y();
+    }
+}
+
+/// Aims new projectiles at their launcher's target, on the team of the ship carrying the launcher.
 pub fn initialise_projectiles(
     mut commands: Commands,
-    query: Query<(Entity, &Instigator), Added<Projectile>>,
-    launcher_query: Query<(Option<&Team>, &Target)>,
+    query: Query<(Entity, &Instigator, Option<&RootInstigator>), Added<Projectile>>,
+    launcher_query: Query<&Target>,
+    team_query: Query<&Team>,
+    parent_query: Query<&Parent>,
 ) {
-    for (entity, instigator) in query.iter() {
-        let Ok((team_opt, target)) = launcher_query.get(instigator.0) else {
+    for (entity, instigator, root) in query.iter() {
+        let Ok(target) = launcher_query.get(instigator.0) else {
             continue;
         };
-        if let Some(team) = team_opt {
-            commands.entity(entity).insert((*team, *target));
-        } else {
-            commands.entity(entity).insert(*target);
+        commands.entity(entity).insert(*target);
+        if let Some(team) = root_instigator((Some(instigator), root), &parent_query)
+            .and_then(|root| team_query.get(root).ok())
+        {
+            commands.entity(entity).insert(*team);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swept_circle_entry() {
+        let path = Vec3::new(100.0, 0.0, 0.0);
+        // passes straight through a circle it would skip over with discrete steps
+        let t = swept_circle_entry(Vec3::ZERO, path, Vec3::new(50.0, 0.0, 0.0), 5.0).unwrap();
+        assert!((t - 0.45).abs() < 1.0e-4);
+        // passes beside the circle
+        assert!(swept_circle_entry(Vec3::ZERO, path, Vec3::new(50.0, 10.0, 0.0), 5.0).is_none());
+        // circle is beyond the end of the path, or behind the start
+        assert!(swept_circle_entry(Vec3::ZERO, path, Vec3::new(150.0, 0.0, 0.0), 5.0).is_none());
+        assert!(swept_circle_entry(Vec3::ZERO, path, Vec3::new(-50.0, 0.0, 0.0), 5.0).is_none());
+        // starts inside the circle
+        assert_eq!(
+            swept_circle_entry(Vec3::ZERO, path, Vec3::new(1.0, 0.0, 0.0), 5.0),
+            Some(0.0)
+        );
+    }
+}
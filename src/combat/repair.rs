@@ -0,0 +1,90 @@
+//! Effects that repair allies, rather than damaging enemies.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    effects::Effect,
+    mortal::{Health, MaxHealth},
+    shields::{recharge_shield, RechargingShield, ShieldCollapsed, ShieldEvent},
+    Target,
+};
+
+/// Restores the hull of the target up to its [MaxHealth], and optionally recharges its shields.
+///
+/// Repairs cannot miss, and pass through shields and armor.
+#[derive(Component, Clone, Copy, Deserialize)]
+pub struct Repair {
+    /// Hull health restored by each application.
+    pub hull: f32,
+    /// Shield health restored by each application. Collapsed shields must reboot before they can be recharged.
+    #[serde(default)]
+    pub shield: f32,
+}
+
+pub fn apply_repairs(
+    mut events: EventWriter<ShieldEvent>,
+    query: Query<(&Repair, &Target), With<Effect>>,
+    mut hull_query: Query<(&mut Health, &MaxHealth)>,
+    mut shield_query: Query<RechargingShield, Without<ShieldCollapsed>>,
+) {
+    for (repair, target) in query.iter() {
+        let Some(target_entity) = target.0 else {
+            continue;
+        };
+
+        if let Ok((mut health, max_health)) = hull_query.get_mut(target_entity) {
+            health.0 = (health.0 + repair.hull).min(max_health.0).max(health.0);
+        }
+
+        if repair.shield <= 0.0 {
+            continue;
+        }
+        if let Ok((entity, mut shield, max_shield, directional)) =
+            shield_query.get_mut(target_entity)
+        {
+            if shield.health < max_shield.0
+                && recharge_shield(&mut shield, max_shield, directional, repair.shield)
+            {
+                events.send(ShieldEvent::Recharged(entity));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::shields::{MaxShieldHP, Shield};
+
+    #[test]
+    fn test_repairs_stop_at_max_health() {
+        let mut app = App::new();
+        app.add_event::<ShieldEvent>()
+            .add_systems(Update, apply_repairs);
+        let ally = app
+            .world
+            .spawn((
+                Health(95.0),
+                MaxHealth(100.0),
+                Shield {
+                    health: 10.0,
+                    radius: 20.0,
+                },
+                MaxShieldHP(50.0),
+            ))
+            .id();
+        app.world.spawn((
+            Repair {
+                hull: 10.0,
+                shield: 5.0,
+            },
+            Target(Some(ally)),
+            Effect,
+        ));
+
+        app.update();
+        assert_eq!(app.world.get::<Health>(ally).unwrap().0, 100.0);
+        assert_eq!(app.world.get::<Shield>(ally).unwrap().health, 15.0);
+    }
+}
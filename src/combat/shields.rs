@@ -1,13 +1,19 @@
 //! Implementation of shields.
 
 use bevy::prelude::*;
+use serde::Deserialize;
 
-use crate::fx::animated::{AnimatedEffects, CreateAnimatedEffect};
+use crate::{
+    fx::animated::{AnimatedEffects, CreateAnimatedEffect},
+    game::GameTimeDelta,
+    math_util::{get_angle_difference, get_heading_to_point},
+};
 
 use super::{
-    attack::{Attack, AttackResult},
-    damage::Damage,
-    effects::{EffectLocation, SourceTransform},
+    attack::{Attack, AttackResult, FollowUpAttack},
+    damage::{Damage, DamageType, LastDamageTimer},
+    effects::{EffectLocation, Effectiveness, SourceTransform},
+    events::{root_instigator, CombatEvent, Instigators},
     Target,
 };
 
@@ -19,21 +25,250 @@ pub struct Shield {
     pub radius: f32,
 }
 
+/// A side of a ship covered by one quadrant of a [DirectionalShield].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShieldFacing {
+    Fore,
+    Aft,
+    Port,
+    Starboard,
+}
+
+impl ShieldFacing {
+    pub const ALL: [ShieldFacing; 4] = [
+        ShieldFacing::Fore,
+        ShieldFacing::Aft,
+        ShieldFacing::Port,
+        ShieldFacing::Starboard,
+    ];
+
+    /// The facing covering a direction, given as an angle relative to the ship's heading.
+    pub fn from_relative_angle(angle: f32) -> Self {
+        let quarter = std::f32::consts::FRAC_PI_4;
+        if angle.abs() <= quarter {
+            ShieldFacing::Fore
+        } else if angle.abs() >= 3.0 * quarter {
+            ShieldFacing::Aft
+        } else if angle > 0.0 {
+            ShieldFacing::Port
+        } else {
+            ShieldFacing::Starboard
+        }
+    }
+
+    /// The direction the facing points, as an angle relative to the ship's heading.
+    pub fn relative_angle(&self) -> f32 {
+        match self {
+            ShieldFacing::Fore => 0.0,
+            ShieldFacing::Port => std::f32::consts::FRAC_PI_2,
+            ShieldFacing::Aft => std::f32::consts::PI,
+            ShieldFacing::Starboard => -std::f32::consts::FRAC_PI_2,
+        }
+    }
+}
+
+/// Splits a [Shield] into four quadrants, each with their own health.
+///
+/// Attacks are absorbed by the quadrant facing the attacker. The [Shield::health] is kept equal to the total of the
+/// quadrants, so the shield collapses once every quadrant is down.
+#[derive(Component, Clone, Copy)]
+pub struct DirectionalShield {
+    pub fore: f32,
+    pub aft: f32,
+    pub port: f32,
+    pub starboard: f32,
+    /// Maximum health of each quadrant.
+    pub quadrant_max: f32,
+    /// Heading from which the shield was last hit, in radians.
+    pub last_hit_heading: Option<f32>,
+}
+
+impl DirectionalShield {
+    /// Splits a shield of the given health evenly between the quadrants.
+    pub fn new(health: f32) -> Self {
+        let quadrant = health / 4.0;
+        DirectionalShield {
+            fore: quadrant,
+            aft: quadrant,
+            port: quadrant,
+            starboard: quadrant,
+            quadrant_max: quadrant,
+            last_hit_heading: None,
+        }
+    }
+
+    pub fn get(&self, facing: ShieldFacing) -> f32 {
+        match facing {
+            ShieldFacing::Fore => self.fore,
+            ShieldFacing::Aft => self.aft,
+            ShieldFacing::Port => self.port,
+            ShieldFacing::Starboard => self.starboard,
+        }
+    }
+
+    pub fn get_mut(&mut self, facing: ShieldFacing) -> &mut f32 {
+        match facing {
+            ShieldFacing::Fore => &mut self.fore,
+            ShieldFacing::Aft => &mut self.aft,
+            ShieldFacing::Port => &mut self.port,
+            ShieldFacing::Starboard => &mut self.starboard,
+        }
+    }
+
+    pub fn total(&self) -> f32 {
+        self.fore + self.aft + self.port + self.starboard
+    }
+
+    /// The facing with the most health.
+    pub fn strongest(&self) -> ShieldFacing {
+        ShieldFacing::ALL
+            .iter()
+            .copied()
+            .fold(ShieldFacing::Fore, |best, facing| {
+                if self.get(facing) > self.get(best) {
+                    facing
+                } else {
+                    best
+                }
+            })
+    }
+
+    /// Restores health split evenly between the quadrants.
+    pub fn restore(&mut self, amount: f32) {
+        let quadrant_max = self.quadrant_max;
+        for facing in ShieldFacing::ALL.iter() {
+            let quadrant = self.get_mut(*facing);
+            *quadrant = (*quadrant + amount / 4.0).min(quadrant_max);
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        ShieldFacing::ALL
+            .iter()
+            .copied()
+            .all(|facing| self.get(facing) >= self.quadrant_max)
+    }
+}
+
+/// Regenerates a [Shield] toward its [MaxShieldHP].
+///
+/// A shield that is reduced to zero health collapses. It stays down for the reboot time, then comes back online and
+/// regenerates as normal.
+#[derive(Component, Clone, Copy, Deserialize)]
+pub struct ShieldRegeneration {
+    /// Shield health restored per second.
+    pub rate: f32,
+    /// Time without taking damage before the shield starts to regenerate, in seconds.
+    pub delay: f32,
+    /// Time a collapsed shield stays down, in seconds.
+    pub reboot_time: f32,
+}
+
+/// Indicates that a shield has collapsed and is rebooting.
+#[derive(Component)]
+pub struct ShieldCollapsed {
+    pub remaining_time: f32,
+}
+
+/// Every component making up a ship's shield, for removing the shield as a whole.
+pub type ShieldComponents = (
+    Shield,
+    DirectionalShield,
+    MaxShieldHP,
+    ShieldAbsorption,
+    ShieldRegeneration,
+    ShieldCollapsed,
+);
+
+/// Raised when the state of a regenerating shield changes.
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub enum ShieldEvent {
+    /// The shield was reduced to zero health and went down.
+    Collapsed(Entity),
+    /// A collapsed shield came back online.
+    Rebooted(Entity),
+    /// The shield regenerated to full health.
+    Recharged(Entity),
+}
+
+/// Multipliers applied to each type of damage dealt to a [Shield].
+///
+/// A multiplier above one drains the shield faster, below one slower. A shield without this component takes all
+/// types of damage equally.
+#[derive(Component, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct ShieldAbsorption {
+    pub kinetic: f32,
+    pub energy: f32,
+    pub explosive: f32,
+    pub emp: f32,
+}
+
+impl Default for ShieldAbsorption {
+    fn default() -> Self {
+        ShieldAbsorption {
+            kinetic: 1.0,
+            energy: 1.0,
+            explosive: 1.0,
+            emp: 1.0,
+        }
+    }
+}
+
+impl ShieldAbsorption {
+    pub fn get(&self, damage_type: DamageType) -> f32 {
+        match damage_type {
+            DamageType::Kinetic => self.kinetic,
+            DamageType::Energy => self.energy,
+            DamageType::Explosive => self.explosive,
+            DamageType::Emp => self.emp,
+        }
+    }
+}
+
 /// Flag component that indicates an attack bypasses shields.
 pub struct BypassShield;
 
+type ShieldQuery<'a> = (
+    &'a mut Shield,
+    &'a GlobalTransform,
+    Option<&'a ShieldAbsorption>,
+    Option<&'a mut LastDamageTimer>,
+    Option<&'a mut DirectionalShield>,
+);
+
+type ShieldedAttack<'a> = (
+    &'a mut Damage,
+    Option<&'a DamageType>,
+    &'a Target,
+    &'a SourceTransform,
+    &'a mut EffectLocation,
+    &'a mut Attack,
+    Option<&'a Effectiveness>,
+    Instigators<'a>,
+    Has<FollowUpAttack>,
+);
+
+/// Shields absorb the damage of attacks from outside their radius, sending a [CombatEvent::Blocked] for each.
 pub fn shield_absorb_damage(
     mut commands: Commands,
-    mut attacks_query: Query<(
-        &mut Damage,
-        &Target,
-        &SourceTransform,
-        &mut EffectLocation,
-        &mut Attack,
-    )>,
-    mut shields_query: Query<(&mut Shield, &GlobalTransform)>,
+    mut events: EventWriter<CombatEvent>,
+    mut attacks_query: Query<ShieldedAttack>,
+    mut shields_query: Query<ShieldQuery>,
+    parent_query: Query<&Parent>,
 ) {
-    for (mut damage, target, source_t, mut hit_loc, mut attack) in attacks_query.iter_mut() {
+    for (
+        mut damage,
+        damage_type,
+        target,
+        source_t,
+        mut hit_loc,
+        mut attack,
+        effectiveness,
+        instigator,
+        follow_up,
+    ) in attacks_query.iter_mut()
+    {
         // does attack target have a shield?
         if target.0.is_none() {
             continue;
@@ -44,7 +279,7 @@ pub fn shield_absorb_damage(
             continue;
         }
 
-        if let Ok((mut shield, shield_transform)) =
+        if let Ok((mut shield, shield_transform, absorption, timer, mut directional)) =
             shields_query.get_mut(target.0.expect("target is none"))
         {
             // if attack from within shield radius, no protection given:
@@ -53,14 +288,58 @@ pub fn shield_absorb_damage(
                 continue;
             }
 
-            // shield blocks incoming damage
-            let absorbed = shield.health.min(damage.0);
+            // a directional shield protects with the quadrant facing the attacker
+            let hit_heading = get_heading_to_point(delta);
+            let facing = directional.as_ref().map(|_| {
+                let heading = get_heading_to_point(shield_transform.up());
+                ShieldFacing::from_relative_angle(get_angle_difference(hit_heading, heading))
+            });
+            let available = match (directional.as_ref(), facing) {
+                (Some(directional), Some(facing)) => directional.get(facing),
+                _ => shield.health,
+            };
+
+            // a shield that is down gives no protection
+            if available <= 0.0 {
+                continue;
+            }
+
+            // attacks that land poorly do little to the shield
+            let effectiveness = effectiveness.map_or(1.0, |e| e.0);
+            if effectiveness <= 0.0 {
+                continue;
+            }
+            let incoming = damage.0 * effectiveness;
+
+            // shield blocks incoming damage, losing health according to the damage type
+            let multiplier = absorption.map_or(1.0, |a| a.get(damage_type.copied().unwrap_or_default()));
+            let absorbed = if multiplier > 0.0 {
+                (available / multiplier).min(incoming)
+            } else {
+                incoming
+            };
 
             if absorbed > 0.0 {
-                shield.health -= absorbed;
-                damage.0 -= absorbed;
+                match (directional.as_mut(), facing) {
+                    (Some(directional), Some(facing)) => {
+                        *directional.get_mut(facing) -= absorbed * multiplier;
+                        directional.last_hit_heading = Some(hit_heading);
+                        shield.health = directional.total();
+                    }
+                    _ => shield.health -= absorbed * multiplier,
+                }
+                damage.0 -= absorbed / effectiveness;
                 hit_loc.0 += delta.normalize() * shield.radius;
                 attack.result = AttackResult::Blocked;
+                if let Some(mut timer) = timer {
+                    timer.0 = 0.0;
+                }
+                events.send(CombatEvent::Blocked {
+                    instigator: root_instigator(instigator, &parent_query),
+                    target: target.0.expect("target is none"),
+                    absorbed,
+                    follow_up,
+                });
 
                 // spawn a 'hit shield' effect
                 commands.spawn(
@@ -78,3 +357,209 @@ pub fn shield_absorb_damage(
         }
     }
 }
+
+/// Collapses regenerating shields that have been reduced to zero health.
+pub fn collapse_shields(
+    mut commands: Commands,
+    mut events: EventWriter<ShieldEvent>,
+    mut query: Query<(Entity, &mut Shield, &ShieldRegeneration), Without<ShieldCollapsed>>,
+) {
+    for (entity, mut shield, regeneration) in query.iter_mut() {
+        if shield.health > 0.0 {
+            continue;
+        }
+        shield.health = 0.0;
+        commands.entity(entity).insert(ShieldCollapsed {
+            remaining_time: regeneration.reboot_time,
+        });
+        events.send(ShieldEvent::Collapsed(entity));
+    }
+}
+
+/// Fraction of [MaxShieldHP] a shield has when it reboots.
+pub const REBOOTED_SHIELD_FRACTION: f32 = 0.25;
+
+pub type RechargingShield<'a> = (
+    Entity,
+    &'a mut Shield,
+    &'a MaxShieldHP,
+    Option<&'a mut DirectionalShield>,
+);
+
+/// Brings collapsed shields back online once their reboot time has passed.
+pub fn reboot_shields(
+    mut commands: Commands,
+    dt: Res<GameTimeDelta>,
+    mut events: EventWriter<ShieldEvent>,
+    mut query: Query<RechargingShield, With<ShieldCollapsed>>,
+    mut collapsed_query: Query<&mut ShieldCollapsed>,
+) {
+    for (entity, mut shield, max_shield, directional) in query.iter_mut() {
+        let Ok(mut collapsed) = collapsed_query.get_mut(entity) else {
+            continue;
+        };
+        collapsed.remaining_time -= dt.0;
+        if collapsed.remaining_time <= 0.0 {
+            shield.health = max_shield.0 * REBOOTED_SHIELD_FRACTION;
+            if let Some(mut directional) = directional {
+                directional.restore(shield.health);
+            }
+            commands.entity(entity).remove::<ShieldCollapsed>();
+            events.send(ShieldEvent::Rebooted(entity));
+        }
+    }
+}
+
+/// Regenerates shields that have not been damaged recently.
+pub fn regenerate_shields(
+    dt: Res<GameTimeDelta>,
+    mut events: EventWriter<ShieldEvent>,
+    mut query: Query<RechargingShield, Without<ShieldCollapsed>>,
+    regeneration_query: Query<(&ShieldRegeneration, &LastDamageTimer)>,
+) {
+    for (entity, mut shield, max_shield, directional) in query.iter_mut() {
+        let Ok((regeneration, timer)) = regeneration_query.get(entity) else {
+            continue;
+        };
+        if shield.health >= max_shield.0 || timer.0 < regeneration.delay {
+            continue;
+        }
+        let amount = regeneration.rate * dt.0;
+        if recharge_shield(&mut shield, max_shield, directional, amount) {
+            events.send(ShieldEvent::Recharged(entity));
+        }
+    }
+}
+
+/// Adds health to a shield, spread over its quadrants if it is directional. Returns true if the shield is now full.
+pub fn recharge_shield(
+    shield: &mut Shield,
+    max_shield: &MaxShieldHP,
+    directional: Option<Mut<DirectionalShield>>,
+    amount: f32,
+) -> bool {
+    let full = match directional {
+        Some(mut directional) => {
+            directional.restore(amount);
+            shield.health = directional.total();
+            directional.is_full()
+        }
+        None => {
+            shield.health = (shield.health + amount).min(max_shield.0);
+            shield.health >= max_shield.0
+        }
+    };
+    if full {
+        shield.health = max_shield.0;
+    }
+    full
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shield_collapses_reboots_and_regenerates() {
+        let mut app = App::new();
+        app.add_event::<ShieldEvent>()
+            .insert_resource(GameTimeDelta(1.0))
+            .add_systems(
+                Update,
+                (collapse_shields, reboot_shields, regenerate_shields).chain(),
+            );
+        let ship = app
+            .world
+            .spawn((
+                Shield {
+                    health: 0.0,
+                    radius: 10.0,
+                },
+                MaxShieldHP(100.0),
+                ShieldRegeneration {
+                    rate: 10.0,
+                    delay: 2.0,
+                    reboot_time: 3.0,
+                },
+                LastDamageTimer(0.0),
+            ))
+            .id();
+
+        app.update();
+        assert!(app.world.get::<ShieldCollapsed>(ship).is_some());
+        app.update();
+        app.update();
+        assert!(app.world.get::<ShieldCollapsed>(ship).is_none());
+        assert_eq!(app.world.get::<Shield>(ship).unwrap().health, 25.0);
+
+        // no regeneration until the delay has passed without damage
+        app.update();
+        assert_eq!(app.world.get::<Shield>(ship).unwrap().health, 25.0);
+        app.world.get_mut::<LastDamageTimer>(ship).unwrap().0 = 2.0;
+        app.update();
+        assert_eq!(app.world.get::<Shield>(ship).unwrap().health, 35.0);
+    }
+
+    #[test]
+    fn test_shield_absorption_depends_on_damage_type() {
+        let mut app = App::new();
+        app.add_event::<CombatEvent>()
+            .add_systems(Update, shield_absorb_damage);
+        let ship = app
+            .world
+            .spawn((
+                Shield {
+                    health: 10.0,
+                    radius: 10.0,
+                },
+                GlobalTransform::default(),
+                ShieldAbsorption {
+                    kinetic: 0.0,
+                    energy: 0.5,
+                    explosive: 2.0,
+                    emp: 1.0,
+                },
+            ))
+            .id();
+        let mut fire = |damage_type: DamageType, damage: f32| {
+            let attack = app
+                .world
+                .spawn((
+                    Damage::new(damage),
+                    damage_type,
+                    Target(Some(ship)),
+                    SourceTransform(GlobalTransform::from_xyz(100.0, 0.0, 0.0)),
+                    EffectLocation(Vec3::ZERO),
+                    Attack::new(1.0),
+                ))
+                .id();
+            app.update();
+            let remaining = app.world.get::<Damage>(attack).unwrap().0;
+            let blocked = app.world.get::<Attack>(attack).unwrap().result == AttackResult::Blocked;
+            app.world.despawn(attack);
+            (remaining, blocked, app.world.get::<Shield>(ship).unwrap().health)
+        };
+
+        // energy drains the shield at half rate, explosives at double rate
+        assert_eq!(fire(DamageType::Energy, 8.0), (0.0, true, 6.0));
+        assert_eq!(fire(DamageType::Explosive, 2.0), (0.0, true, 2.0));
+        // a multiplier of zero blocks the damage without draining the shield
+        assert_eq!(fire(DamageType::Kinetic, 50.0), (0.0, true, 2.0));
+        // only the damage the remaining shield can soak up is absorbed
+        assert_eq!(fire(DamageType::Explosive, 5.0), (4.0, true, 0.0));
+        // a shield that is down blocks nothing, even for damage it would absorb for free
+        assert_eq!(fire(DamageType::Kinetic, 50.0), (50.0, false, 0.0));
+    }
+
+    #[test]
+    fn test_shield_facing_from_relative_angle() {
+        let pi = std::f32::consts::PI;
+        assert_eq!(ShieldFacing::from_relative_angle(0.1), ShieldFacing::Fore);
+        assert_eq!(ShieldFacing::from_relative_angle(0.5 * pi), ShieldFacing::Port);
+        assert_eq!(ShieldFacing::from_relative_angle(-0.5 * pi), ShieldFacing::Starboard);
+        assert_eq!(ShieldFacing::from_relative_angle(-0.9 * pi), ShieldFacing::Aft);
+        for facing in ShieldFacing::ALL.iter() {
+            assert_eq!(ShieldFacing::from_relative_angle(facing.relative_angle()), *facing);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Statistics of how each ship and team has fared in combat, tallied from [CombatEvent]s.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::{events::CombatEvent, Team};
+
+/// Combat statistics of a ship or team.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct CombatStats {
+    /// Damage dealt to the hulls and shields of targets.
+    pub damage_dealt: f32,
+    /// Damage taken to the hull.
+    pub damage_taken: f32,
+    /// Damage absorbed by shields.
+    pub shield_absorbed: f32,
+    /// Attacks made, whether they hit, missed or were blocked. Blast fragments and later ticks of a beam are part of
+    /// the shot that caused them.
+    pub shots_fired: u32,
+    /// Attacks that were not evaded, including those blocked by shields.
+    pub hits: u32,
+    /// Kills credited for landing the killing blow.
+    pub kills: u32,
+}
+
+impl CombatStats {
+    /// The fraction of attacks that were not evaded.
+    pub fn hit_rate(&self) -> f32 {
+        if self.shots_fired == 0 {
+            return 0.0;
+        }
+        self.hits as f32 / self.shots_fired as f32
+    }
+}
+
+/// Combat statistics of every ship and team over the battle, including ships that have since died.
+///
+/// Teams are keyed by their [Team] number.
+#[derive(Resource, Default)]
+pub struct BattleStats {
+    pub ships: HashMap<Entity, CombatStats>,
+    pub teams: HashMap<i32, CombatStats>,
+    /// The team of each ship when it was first seen, so that ships that have since died still count for their team.
+    ship_teams: HashMap<Entity, i32>,
+}
+
+impl BattleStats {
+    /// Updates the statistics of the ship, and of its team if it has one.
+    fn update(
+        &mut self,
+        ship: Entity,
+        team_query: &Query<&Team>,
+        update: impl Fn(&mut CombatStats),
+    ) {
+        update(self.ships.entry(ship).or_default());
+        if let Some(team) = self.team_of(ship, team_query) {
+            update(self.teams.entry(team).or_default());
+        }
+    }
+
+    fn team_of(&mut self, ship: Entity, team_query: &Query<&Team>) -> Option<i32> {
+        if let Ok(team) = team_query.get(ship) {
+            self.ship_teams.insert(ship, team.0);
+        }
+        self.ship_teams.get(&ship).copied()
+    }
+}
+
+/// Tallies combat events into the [BattleStats], and copies them to each ship's [CombatStats].
+pub fn record_combat_stats(
+    mut events: EventReader<CombatEvent>,
+    mut battle_stats: ResMut<BattleStats>,
+    mut stats_query: Query<&mut CombatStats>,
+    team_query: Query<&Team>,
+) {
+    let mut updated = Vec::new();
+    for event in events.read() {
+        match *event {
+            CombatEvent::Hit {
+                instigator,
+                target,
+                damage,
+                follow_up,
+                ..
+            } => {
+                if let Some(instigator) = instigator {
+                    battle_stats.update(instigator, &team_query, |stats| {
+                        stats.damage_dealt += damage;
+                        if !follow_up {
+                            stats.shots_fired += 1;
+                            stats.hits += 1;
+                        }
+                    });
+                    updated.push(instigator);
+                }
+                battle_stats.update(target, &team_query, |stats| stats.damage_taken += damage);
+                updated.push(target);
+            }
+            CombatEvent::Missed {
+                instigator,
+                follow_up,
+                ..
+            } => {
+                if let (Some(instigator), false) = (instigator, follow_up) {
+                    battle_stats.update(instigator, &team_query, |stats| stats.shots_fired += 1);
+                    updated.push(instigator);
+                }
+            }
+            CombatEvent::Blocked {
+                instigator,
+                target,
+                absorbed,
+                follow_up,
+            } => {
+                if let Some(instigator) = instigator {
+                    battle_stats.update(instigator, &team_query, |stats| {
+                        stats.damage_dealt += absorbed;
+                        if !follow_up {
+                            stats.shots_fired += 1;
+                            stats.hits += 1;
+                        }
+                    });
+                    updated.push(instigator);
+                }
+                battle_stats.update(target, &team_query, |stats| {
+                    stats.shield_absorbed += absorbed
+                });
+                updated.push(target);
+            }
+            CombatEvent::Killed { instigator, .. } => {
+                if let Some(instigator) = instigator {
+                    battle_stats.update(instigator, &team_query, |stats| stats.kills += 1);
+                    updated.push(instigator);
+                }
+            }
+            CombatEvent::Spawned { entity, .. } => {
+                battle_stats.team_of(entity, &team_query);
+            }
+        }
+    }
+
+    for ship in updated {
+        if let (Ok(mut stats), Some(recorded)) =
+            (stats_query.get_mut(ship), battle_stats.ships.get(&ship))
+        {
+            *stats = *recorded;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_are_tallied_for_ships_and_teams() {
+        let mut app = App::new();
+        app.add_event::<CombatEvent>()
+            .init_resource::<BattleStats>()
+            .add_systems(Update, record_combat_stats);
+        let attacker = app.world.spawn((CombatStats::default(), Team(1))).id();
+        let target = app.world.spawn((CombatStats::default(), Team(2))).id();
+
+        let mut events = app.world.resource_mut::<Events<CombatEvent>>();
+        events.send(CombatEvent::Missed {
+            instigator: Some(attacker),
+            target,
+            follow_up: false,
+        });
+        events.send(CombatEvent::Blocked {
+            instigator: Some(attacker),
+            target,
+            absorbed: 5.0,
+            follow_up: false,
+        });
+        events.send(CombatEvent::Hit {
+            instigator: Some(attacker),
+            target,
+            damage: 10.0,
+            critical: false,
+            follow_up: false,
+        });
+        // a blast fragment from the same shot
+        events.send(CombatEvent::Hit {
+            instigator: Some(attacker),
+            target,
+            damage: 4.0,
+            critical: false,
+            follow_up: true,
+        });
+        events.send(CombatEvent::Killed {
+            instigator: Some(attacker),
+            entity: target,
+        });
+        app.update();
+
+        let stats = *app.world.get::<CombatStats>(attacker).unwrap();
+        assert_eq!(stats.damage_dealt, 19.0);
+        assert_eq!(stats.shots_fired, 3);
+        assert_eq!(stats.hit_rate(), 2.0 / 3.0);
+        assert_eq!(stats.kills, 1);
+        let battle_stats = app.world.resource::<BattleStats>();
+        assert_eq!(battle_stats.teams[&1], stats);
+        assert_eq!(battle_stats.teams[&2].damage_taken, 14.0);
+        assert_eq!(battle_stats.teams[&2].shield_absorbed, 5.0);
+    }
+
+    #[test]
+    fn test_dead_ships_still_count_for_their_team() {
+        let mut app = App::new();
+        app.add_event::<CombatEvent>()
+            .init_resource::<BattleStats>()
+            .add_systems(Update, record_combat_stats);
+        let attacker = app.world.spawn((CombatStats::default(), Team(1))).id();
+        let target = app.world.spawn((CombatStats::default(), Team(2))).id();
+        app.world
+            .resource_mut::<Events<CombatEvent>>()
+            .send(CombatEvent::Spawned {
+                instigator: None,
+                entity: attacker,
+            });
+        app.update();
+
+        app.world.despawn(attacker);
+        app.world
+            .resource_mut::<Events<CombatEvent>>()
+            .send(CombatEvent::Killed {
+                instigator: Some(attacker),
+                entity: target,
+            });
+        app.update();
+
+        assert_eq!(app.world.resource::<BattleStats>().teams[&1].kills, 1);
+    }
+}
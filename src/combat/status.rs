@@ -0,0 +1,445 @@
+//! Status effects, which linger on a target after the attack that inflicted them.
+//!
+//! Each status is its own entity with a [Lifetime], pointing at the entity it affects. A target holds at most one status
+//! of each kind, and inflicting it again refreshes or stacks the existing status. Stats changed by a status are
+//! restored when it expires.
+
+use std::mem::discriminant;
+
+use bevy::{ecs::query::QueryData, prelude::*, utils::HashMap};
+use serde::Deserialize;
+
+use crate::{
+    game::GameTimeDelta,
+    movement::{MaxTurnSpeed, Thrust},
+};
+
+use super::{
+    attack::{Attack, AttackResult},
+    damage::LastDamageTimer,
+    effects::Effect,
+    evasion::Evasion,
+    events::{root_instigator, Instigators, LastAttacker},
+    lifetime::{Expired, Lifetime},
+    mortal::Health,
+    subsystems::{Subsystem, SubsystemKind},
+    tools::TargettedTool,
+    Target,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum StatusKind {
+    /// Disarms the target's weapons and cuts its thrust.
+    Emp,
+    /// Deals damage per second to the target's hull for each stack, ignoring shields and armor.
+    Burning(f32),
+    /// Multiplies the target's maximum turn speed once for each stack.
+    Slowed(f32),
+    /// Lowers the target's evasion by the given amount for each stack.
+    Marked(f32),
+}
+
+/// What happens when a status is inflicted on a target that already has it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+pub enum Stacking {
+    /// The duration is reset.
+    #[default]
+    Refresh,
+    /// A stack is added, up to the given maximum, and the duration is reset.
+    Stack(u32),
+}
+
+/// A status inflicted by an attack when it hits.
+#[derive(Component, Clone, Copy, Deserialize)]
+pub struct InflictStatus {
+    pub kind: StatusKind,
+    /// How long the status lasts, in seconds.
+    pub duration: f32,
+    #[serde(default)]
+    pub stacking: Stacking,
+}
+
+/// A status affecting the target entity.
+#[derive(Component)]
+pub struct StatusEffect {
+    pub target: Entity,
+    pub kind: StatusKind,
+    pub stacks: u32,
+    /// The root instigator responsible for inflicting the status, which is credited with kills by burning.
+    pub instigator: Option<Entity>,
+    /// Value of the stat changed by the status before it took hold. For an EMP this is the thrust with every engine
+    /// intact, so that engines destroyed while it lasts stay lost.
+    original: Option<f32>,
+    /// Tools disarmed by an EMP, which are re-armed when it ends.
+    disarmed: Vec<Entity>,
+}
+
+impl StatusEffect {
+    pub fn new(target: Entity, kind: StatusKind, instigator: Option<Entity>) -> Self {
+        StatusEffect {
+            target,
+            kind,
+            stacks: 1,
+            instigator,
+            original: None,
+            disarmed: Vec::new(),
+        }
+    }
+
+    /// Replaces the value of the stat before the status took hold, e.g. when the target's template is reloaded.
+    ///
+    /// Returns the value of the stat while the status lasts. A status that has not taken hold yet leaves the stat as
+    /// it is.
+    pub fn rebase(&mut self, base: f32) -> f32 {
+        if self.original.is_none() {
+            return base;
+        }
+        self.original = Some(base);
+        let stacks = self.stacks as i32;
+        match self.kind {
+            StatusKind::Emp => 0.0,
+            StatusKind::Burning(_) => base,
+            StatusKind::Slowed(factor) => base * factor.powi(stacks),
+            StatusKind::Marked(penalty) => base - penalty * stacks as f32,
+        }
+    }
+
+    /// Inflicts the status again, following the stacking rules.
+    fn reapply(&mut self, lifetime: &mut Lifetime, inflict: &InflictStatus) {
+        self.kind = inflict.kind;
+        if let Stacking::Stack(max_stacks) = inflict.stacking {
+            self.stacks = (self.stacks + 1).min(max_stacks.max(1));
+        }
+        lifetime.seconds_remaining = lifetime.seconds_remaining.max(inflict.duration);
+    }
+}
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct StatusTarget {
+    health: Option<&'static mut Health>,
+    last_damage: Option<&'static mut LastDamageTimer>,
+    thrust: Option<&'static mut Thrust>,
+    max_turn_speed: Option<&'static mut MaxTurnSpeed>,
+    evasion: Option<&'static mut Evasion>,
+    children: Option<&'static Children>,
+}
+
+type StatusAttack<'a> = (
+    &'a InflictStatus,
+    &'a Attack,
+    &'a Target,
+    Instigators<'a>,
+);
+
+/// Inflicts statuses carried by attacks that hit.
+pub fn inflict_statuses(
+    mut commands: Commands,
+    attack_query: Query<StatusAttack, With<Effect>>,
+    mut status_query: Query<(&mut StatusEffect, &mut Lifetime)>,
+    parent_query: Query<&Parent>,
+) {
+    // Statuses inflicted this step, so that several hits merge into one status.
+    let mut inflicted = HashMap::new();
+
+    for (inflict, attack, target, instigator) in attack_query.iter() {
+        if attack.result != AttackResult::Hit {
+            continue;
+        }
+        let Some(target_entity) = target.0 else {
+            continue;
+        };
+
+        let existing = status_query.iter_mut().find(|(status, _)| {
+            status.target == target_entity
+                && discriminant(&status.kind) == discriminant(&inflict.kind)
+        });
+        if let Some((mut status, mut lifetime)) = existing {
+            status.reapply(&mut lifetime, inflict);
+            continue;
+        }
+
+        let key = (target_entity, discriminant(&inflict.kind));
+        match inflicted.get_mut(&key) {
+            Some((status, lifetime)) => StatusEffect::reapply(status, lifetime, inflict),
+            None => {
+                inflicted.insert(
+                    key,
+                    (
+                        StatusEffect::new(
+                            target_entity,
+                            inflict.kind,
+                            root_instigator(instigator, &parent_query),
+                        ),
+                        Lifetime {
+                            seconds_remaining: inflict.duration,
+                        },
+                    ),
+                );
+            }
+        }
+    }
+
+    for (_, status) in inflicted.drain() {
+        commands.spawn(status);
+    }
+}
+
+/// Total thrust provided by the destroyed engines among the children.
+fn destroyed_engine_thrust(children: Option<&Children>, subsystem_query: &Query<&Subsystem>) -> f32 {
+    subsystem_query
+        .iter_many(children.into_iter().flatten())
+        .filter(|subsystem| subsystem.destroyed)
+        .map(|subsystem| match subsystem.kind {
+            SubsystemKind::Engine(thrust) => thrust,
+            _ => 0.0,
+        })
+        .sum()
+}
+
+/// Applies each status to its target, and removes statuses whose target no longer exists.
+pub fn update_statuses(
+    mut commands: Commands,
+    dt: Res<GameTimeDelta>,
+    mut status_query: Query<(Entity, &mut StatusEffect), Without<Expired>>,
+    mut target_query: Query<StatusTarget>,
+    mut tool_query: Query<&mut TargettedTool>,
+    subsystem_query: Query<&Subsystem>,
+) {
+    for (entity, mut status) in status_query.iter_mut() {
+        let Ok(target) = target_query.get_mut(status.target) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let stacks = status.stacks as f32;
+
+        match status.kind {
+            StatusKind::Emp => {
+                let children = target.children;
+                if let Some(mut thrust) = target.thrust {
+                    status.original.get_or_insert_with(|| {
+                        thrust.0 + destroyed_engine_thrust(children, &subsystem_query)
+                    });
+                    thrust.0 = 0.0;
+                }
+                for &child in target.children.into_iter().flatten() {
+                    if let Ok(mut tool) = tool_query.get_mut(child) {
+                        if tool.armed {
+                            tool.armed = false;
+                            status.disarmed.push(child);
+                        }
+                    }
+                }
+            }
+            StatusKind::Burning(damage_per_second) => {
+                let mut killed = false;
+                if let Some(mut health) = target.health {
+                    let was_alive = health.0 > 0.0;
+                    health.0 -= damage_per_second * stacks * dt.0;
+                    killed = was_alive && health.0 <= 0.0;
+                }
+                if let Some(mut timer) = target.last_damage {
+                    timer.0 = 0.0;
+                }
+                if let (Some(instigator), true) = (status.instigator, killed) {
+                    commands
+                        .entity(status.target)
+                        .insert(LastAttacker(instigator));
+                }
+            }
+            StatusKind::Slowed(factor) => {
+                if let Some(mut max_turn_speed) = target.max_turn_speed {
+                    let original = *status
+                        .original
+                        .get_or_insert(max_turn_speed.radians_per_second);
+                    max_turn_speed.radians_per_second = original * factor.powf(stacks);
+                }
+            }
+            StatusKind::Marked(penalty) => {
+                if let Some(mut evasion) = target.evasion {
+                    let original = *status.original.get_or_insert(evasion.base);
+                    evasion.base = original - penalty * stacks;
+                }
+            }
+        }
+    }
+}
+
+/// Restores the stats changed by statuses that have just expired.
+pub fn end_expired_statuses(
+    status_query: Query<&StatusEffect, Added<Expired>>,
+    mut target_query: Query<StatusTarget>,
+    mut tool_query: Query<(&mut TargettedTool, Option<&Subsystem>)>,
+    subsystem_query: Query<&Subsystem>,
+) {
+    for status in status_query.iter() {
+        let Ok(target) = target_query.get_mut(status.target) else {
+            continue;
+        };
+        let Some(original) = status.original else {
+            continue;
+        };
+
+        match status.kind {
+            StatusKind::Emp => {
+                if let Some(mut thrust) = target.thrust {
+                    thrust.0 = (original
+                        - destroyed_engine_thrust(target.children, &subsystem_query))
+                    .max(0.0);
+                }
+            }
+            StatusKind::Burning(_) => {}
+            StatusKind::Slowed(_) => {
+                if let Some(mut max_turn_speed) = target.max_turn_speed {
+                    max_turn_speed.radians_per_second = original;
+                }
+            }
+            StatusKind::Marked(_) => {
+                if let Some(mut evasion) = target.evasion {
+                    evasion.base = original;
+                }
+            }
+        }
+    }
+
+    // Weapons knocked out while disabled stay disarmed.
+    for status in status_query.iter() {
+        for &tool in status.disarmed.iter() {
+            if let Ok((mut tool, subsystem)) = tool_query.get_mut(tool) {
+                tool.armed = subsystem.is_none_or(|subsystem| !subsystem.destroyed);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::lifetime::update_lifetimes;
+
+    #[test]
+    fn test_statuses_stack_and_restore_when_expired() {
+        let mut app = App::new();
+        app.insert_resource(GameTimeDelta(0.1)).add_systems(
+            Update,
+            (
+                update_lifetimes,
+                end_expired_statuses,
+                inflict_statuses,
+                update_statuses,
+            )
+                .chain(),
+        );
+        let tool = app
+            .world
+            .spawn(TargettedTool {
+                cone: 1.0,
+                range: 100.0,
+                armed: true,
+                firing: false,
+            })
+            .id();
+        let target = app
+            .world
+            .spawn((Thrust(200.0), MaxTurnSpeed::new(2.0)))
+            .push_children(&[tool])
+            .id();
+        let hit = |app: &mut App, kind: StatusKind| {
+            app.world
+                .spawn((
+                    InflictStatus {
+                        kind,
+                        duration: 0.5,
+                        stacking: Stacking::Stack(3),
+                    },
+                    Attack::new(1.0),
+                    Target(Some(target)),
+                    Effect,
+                ))
+                .id()
+        };
+
+        let attacks = [
+            hit(&mut app, StatusKind::Emp),
+            hit(&mut app, StatusKind::Slowed(0.5)),
+            hit(&mut app, StatusKind::Slowed(0.5)),
+        ];
+        app.update();
+        for attack in attacks.iter() {
+            app.world.despawn(*attack);
+        }
+        app.update();
+        assert_eq!(app.world.get::<Thrust>(target).unwrap().0, 0.0);
+        assert_eq!(
+            app.world
+                .get::<MaxTurnSpeed>(target)
+                .unwrap()
+                .radians_per_second,
+            0.5
+        );
+        assert!(!app.world.get::<TargettedTool>(tool).unwrap().armed);
+
+        for _ in 0..6 {
+            app.update();
+        }
+        assert_eq!(app.world.get::<Thrust>(target).unwrap().0, 200.0);
+        assert_eq!(
+            app.world
+                .get::<MaxTurnSpeed>(target)
+                .unwrap()
+                .radians_per_second,
+            2.0
+        );
+        assert!(app.world.get::<TargettedTool>(tool).unwrap().armed);
+    }
+
+    #[test]
+    fn test_engines_destroyed_during_emp_stay_lost() {
+        let mut app = App::new();
+        app.insert_resource(GameTimeDelta(0.1)).add_systems(
+            Update,
+            (
+                update_lifetimes,
+                end_expired_statuses,
+                inflict_statuses,
+                update_statuses,
+            )
+                .chain(),
+        );
+        let mut damaged_engine = Subsystem::new(SubsystemKind::Engine(50.0), 10.0, 8.0);
+        damaged_engine.destroyed = true;
+        let damaged_engine = app.world.spawn(damaged_engine).id();
+        let engine = app
+            .world
+            .spawn(Subsystem::new(SubsystemKind::Engine(100.0), 10.0, 8.0))
+            .id();
+        let target = app
+            .world
+            .spawn(Thrust(150.0))
+            .push_children(&[damaged_engine, engine])
+            .id();
+        let attack = app
+            .world
+            .spawn((
+                InflictStatus {
+                    kind: StatusKind::Emp,
+                    duration: 0.5,
+                    stacking: Stacking::Refresh,
+                },
+                Attack::new(1.0),
+                Target(Some(target)),
+                Effect,
+            ))
+            .id();
+        app.update();
+        app.world.despawn(attack);
+        app.update();
+        assert_eq!(app.world.get::<Thrust>(target).unwrap().0, 0.0);
+
+        app.world.get_mut::<Subsystem>(engine).unwrap().destroyed = true;
+        for _ in 0..6 {
+            app.update();
+        }
+        assert_eq!(app.world.get::<Thrust>(target).unwrap().0, 50.0);
+    }
+}
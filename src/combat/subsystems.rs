@@ -0,0 +1,266 @@
+//! Subsystems of a ship, such as engines and weapons, which can be knocked out by hits that land near them.
+//!
+//! Subsystems are child entities of the hull. Hits that get through the shields land on the edge of the hull facing the
+//! attacker, and damage the nearest subsystem covering that point. Hits still damage the hull as usual.
+//!
+//! The position and radius of a subsystem are both in the hull's local space, so they scale with the ship.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::movement::Thrust;
+
+use super::{
+    attack::{Attack, AttackResult},
+    damage::Damage,
+    effects::{Effect, EffectLocation, Effectiveness, SourceTransform},
+    projectile::CircularHitBox,
+    shields::ShieldComponents,
+    tools::TargettedTool,
+    Target,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum SubsystemKind {
+    /// Provides the given amount of the ship's [Thrust], which is lost when the engine is destroyed.
+    Engine(f32),
+    /// A weapon, whose [TargettedTool] is disarmed when it is destroyed.
+    Weapon,
+    /// Powers the ship's shields, which go down for good when it is destroyed.
+    ShieldGenerator,
+}
+
+/// Radius of the subsystem covering a weapon hardpoint, in the hull's local space.
+pub const WEAPON_SUBSYSTEM_RADIUS: f32 = 48.0;
+
+/// A damageable part of the parent ship.
+#[derive(Component, Clone, Copy)]
+pub struct Subsystem {
+    pub kind: SubsystemKind,
+    pub health: f32,
+    pub max_health: f32,
+    /// Hits landing within this distance of the subsystem damage it, in the hull's local space.
+    pub radius: f32,
+    pub destroyed: bool,
+}
+
+impl Subsystem {
+    pub fn new(kind: SubsystemKind, health: f32, radius: f32) -> Self {
+        Subsystem {
+            kind,
+            health,
+            max_health: health,
+            radius,
+            destroyed: false,
+        }
+    }
+}
+
+/// Moves the location of hits onto the edge of the target's hit box, on the side facing the attacker.
+pub fn locate_hull_hits(
+    mut attack_query: Query<
+        (&mut EffectLocation, &SourceTransform, &Target, &Attack),
+        With<Effect>,
+    >,
+    hull_query: Query<(&GlobalTransform, &CircularHitBox)>,
+) {
+    for (mut location, source, target, attack) in attack_query.iter_mut() {
+        if attack.result != AttackResult::Hit {
+            continue;
+        }
+        let Some(target_entity) = target.0 else {
+            continue;
+        };
+        let Ok((hull_transform, hit_box)) = hull_query.get(target_entity) else {
+            continue;
+        };
+
+        let centre = hull_transform.translation();
+        let delta = source.0.translation() - centre;
+        if delta.length_squared() > 0.0 {
+            location.0 = centre + delta.normalize() * hit_box.radius;
+        }
+    }
+}
+
+type SubsystemAttack<'a> = (
+    &'a Damage,
+    &'a EffectLocation,
+    &'a Target,
+    &'a Attack,
+    Option<&'a Effectiveness>,
+);
+
+/// Damages the subsystem nearest to where each hit lands.
+pub fn damage_subsystems(
+    attack_query: Query<SubsystemAttack, With<Effect>>,
+    hull_query: Query<(&Children, &GlobalTransform)>,
+    mut subsystem_query: Query<(&mut Subsystem, &Transform)>,
+) {
+    for (damage, location, target, attack, effectiveness) in attack_query.iter() {
+        if attack.result != AttackResult::Hit || damage.0 <= 0.0 {
+            continue;
+        }
+        let Some((children, hull_transform)) =
+            target.0.and_then(|target| hull_query.get(target).ok())
+        else {
+            continue;
+        };
+        let local_location = hull_transform
+            .affine()
+            .inverse()
+            .transform_point3(location.0);
+
+        let mut nearest = None;
+        let mut nearest_distance = f32::INFINITY;
+        for &child in children.iter() {
+            let Ok((subsystem, transform)) = subsystem_query.get(child) else {
+                continue;
+            };
+            let distance = (transform.translation - local_location).length();
+            if !subsystem.destroyed && distance <= subsystem.radius && distance < nearest_distance {
+                nearest = Some(child);
+                nearest_distance = distance;
+            }
+        }
+
+        if let Some(Ok((mut subsystem, _))) = nearest.map(|child| subsystem_query.get_mut(child)) {
+            subsystem.health -= damage.0 * effectiveness.map_or(1.0, |e| e.0);
+        }
+    }
+}
+
+/// Knocks out subsystems whose health has run out.
+pub fn destroy_subsystems(
+    mut commands: Commands,
+    mut query: Query<(&mut Subsystem, &Parent, Option<&mut TargettedTool>)>,
+    mut thrust_query: Query<&mut Thrust>,
+) {
+    for (mut subsystem, parent, tool) in query.iter_mut() {
+        if subsystem.destroyed || subsystem.health > 0.0 {
+            continue;
+        }
+        subsystem.destroyed = true;
+
+        match subsystem.kind {
+            SubsystemKind::Engine(engine_thrust) => {
+                if let Ok(mut thrust) = thrust_query.get_mut(parent.get()) {
+                    thrust.0 = (thrust.0 - engine_thrust).max(0.0);
+                }
+            }
+            SubsystemKind::Weapon => {
+                if let Some(mut tool) = tool {
+                    tool.armed = false;
+                }
+            }
+            SubsystemKind::ShieldGenerator => {
+                commands.entity(parent.get()).remove::<ShieldComponents>();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::shields::{
+        DirectionalShield, MaxShieldHP, Shield, ShieldAbsorption, ShieldCollapsed,
+        ShieldRegeneration,
+    };
+
+    #[test]
+    fn test_hits_damage_nearest_subsystem() {
+        let mut app = App::new();
+        app.add_systems(
+            Update,
+            (locate_hull_hits, damage_subsystems, destroy_subsystems).chain(),
+        );
+        let engine = app
+            .world
+            .spawn((
+                Subsystem::new(SubsystemKind::Engine(150.0), 10.0, 8.0),
+                Transform::from_xyz(0.0, -20.0, 0.0),
+            ))
+            .id();
+        let weapon = app
+            .world
+            .spawn((
+                Subsystem::new(SubsystemKind::Weapon, 10.0, 8.0),
+                Transform::from_xyz(0.0, 20.0, 0.0),
+                TargettedTool {
+                    cone: 1.0,
+                    range: 100.0,
+                    armed: true,
+                    firing: false,
+                },
+            ))
+            .id();
+        let hull = app
+            .world
+            .spawn((
+                Thrust(200.0),
+                // the hull is drawn at half size, so its edge is 20 units out in local space
+                CircularHitBox { radius: 10.0 },
+                GlobalTransform::from_scale(Vec3::splat(0.5)),
+            ))
+            .push_children(&[engine, weapon])
+            .id();
+
+        // Attack from behind the ship.
+        app.world.spawn((
+            Damage::new(12.0),
+            Attack::new(1.0),
+            EffectLocation(Vec3::ZERO),
+            SourceTransform(GlobalTransform::from_translation(Vec3::new(
+                0.0, -100.0, 0.0,
+            ))),
+            Target(Some(hull)),
+            Effect,
+        ));
+        app.update();
+
+        assert!(app.world.get::<Subsystem>(engine).unwrap().destroyed);
+        assert_eq!(app.world.get::<Thrust>(hull).unwrap().0, 50.0);
+        assert_eq!(app.world.get::<Subsystem>(weapon).unwrap().health, 10.0);
+        assert!(app.world.get::<TargettedTool>(weapon).unwrap().armed);
+    }
+
+    #[test]
+    fn test_destroyed_shield_generator_takes_down_the_whole_shield() {
+        let mut app = App::new();
+        app.add_systems(Update, destroy_subsystems);
+        let mut generator = Subsystem::new(SubsystemKind::ShieldGenerator, 10.0, 8.0);
+        generator.health = 0.0;
+        let generator = app.world.spawn(generator).id();
+        let hull = app
+            .world
+            .spawn((
+                Shield {
+                    health: 0.0,
+                    radius: 20.0,
+                },
+                DirectionalShield::new(50.0),
+                MaxShieldHP(50.0),
+                ShieldAbsorption::default(),
+                ShieldRegeneration {
+                    rate: 1.0,
+                    delay: 1.0,
+                    reboot_time: 1.0,
+                },
+                ShieldCollapsed {
+                    remaining_time: 1.0,
+                },
+            ))
+            .push_children(&[generator])
+            .id();
+        app.update();
+
+        let hull = app.world.entity(hull);
+        assert!(!hull.contains::<Shield>());
+        assert!(!hull.contains::<DirectionalShield>());
+        assert!(!hull.contains::<MaxShieldHP>());
+        assert!(!hull.contains::<ShieldAbsorption>());
+        assert!(!hull.contains::<ShieldRegeneration>());
+        assert!(!hull.contains::<ShieldCollapsed>());
+    }
+}
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use super::Team;
+
 #[derive(Clone, Copy, Component)]
 #[derive(Default)]
 pub struct Target(pub Option<Entity>);
@@ -8,6 +10,25 @@ pub struct Target(pub Option<Entity>);
 #[derive(Clone, Copy, Component, Default)]
 pub struct InheritTargetFromParent;
 
+/// Indicates that an entity should be on the same team as it's parent.
+#[derive(Clone, Copy, Component, Default)]
+pub struct InheritTeamFromParent;
+
+pub fn copy_teams_from_parents(
+    mut commands: Commands,
+    query: Query<(Entity, &Parent, Option<&Team>), With<InheritTeamFromParent>>,
+    team_query: Query<&Team>,
+) {
+    for (entity, parent, team) in query.iter() {
+        let Ok(parent_team) = team_query.get(parent.get()) else {
+            continue;
+        };
+        if team != Some(parent_team) {
+            commands.entity(entity).insert(*parent_team);
+        }
+    }
+}
+
 pub fn copy_targets_from_parents(
      query: Query<(Entity, &Parent), With<InheritTargetFromParent>>,
     mut targetter_query: Query<&mut Target>
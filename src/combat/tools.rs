@@ -1,7 +1,7 @@
 //! Functionality for devices that can be used to create some effect - be it spawn a projectile, damage or heal a target, etc.
 
 use super::{effects::Effector, Target};
-use crate::game::GameTimeDelta;
+use crate::{game::GameTimeDelta, math_util::get_intercept_point, movement::Velocity};
 use bevy::prelude::*;
 
 /// A tool that applies an effect to a target
@@ -17,6 +17,24 @@ pub struct TargettedTool {
     pub firing: bool,
 }
 
+impl TargettedTool {
+    /// Whether a point lies within the range and cone of the tool.
+    pub fn in_reach(&self, transform: &GlobalTransform, point: Vec3) -> bool {
+        let delta = point - transform.translation();
+        if delta.length_squared() > self.range * self.range {
+            return false;
+        }
+        let projection = delta.normalize().dot(transform.up().normalize());
+        projection >= (self.cone / 2.0).cos()
+    }
+}
+
+/// A tool that fires projectiles, which aims at the point where its projectiles will meet a moving target.
+#[derive(Component, Clone, Copy)]
+pub struct LeadTarget {
+    pub projectile_speed: f32,
+}
+
 /// Cooldown timer for a tool.
 #[derive(Component)]
 pub struct Cooldown {
@@ -50,11 +68,19 @@ pub fn update_cooldowns(dt: Res<GameTimeDelta>, mut query: Query<&mut Cooldown>)
     }
 }
 
+type ToolQuery<'a> = (
+    &'a mut Cooldown,
+    &'a mut TargettedTool,
+    &'a Target,
+    &'a GlobalTransform,
+    Option<&'a LeadTarget>,
+);
+
 pub fn fire_targetted_tools(
-    mut query: Query<(&mut Cooldown, &mut TargettedTool, &Target, &GlobalTransform)>,
-    pos_query: Query<&GlobalTransform>,
+    mut query: Query<ToolQuery>,
+    pos_query: Query<(&GlobalTransform, Option<&Velocity>)>,
 ) {
-    for (mut cooldown, mut tool, target, transform) in query.iter_mut() {
+    for (mut cooldown, mut tool, target, transform, lead) in query.iter_mut() {
         if target.0.is_none() {
             continue;
         }
@@ -69,17 +95,22 @@ pub fn fire_targetted_tools(
 
         match pos_query.get(target.0.expect("target is None")) {
             Err(_) => continue,
-            Ok(target_transform) => {
-                let delta = target_transform.translation() - transform.translation();
-
-                // Cannot fire if out of weapon range
-                if delta.length_squared() > tool.range * tool.range {
-                    continue;
+            Ok((target_transform, target_velocity)) => {
+                // Aim where the projectile will meet the target.
+                let mut aim_point = target_transform.translation();
+                if let (Some(lead), Some(velocity)) = (lead, target_velocity) {
+                    if let Some(intercept) = get_intercept_point(
+                        transform.translation(),
+                        aim_point,
+                        velocity.0,
+                        lead.projectile_speed,
+                    ) {
+                        aim_point = intercept;
+                    }
                 }
 
-                // Only fire when target is within weapon cone.
-                let projection = delta.normalize().dot(transform.up().normalize());
-                if projection < (tool.cone / 2.0).cos() {
+                // Only fire when the target is within weapon range and cone.
+                if !tool.in_reach(transform, aim_point) {
                     continue;
                 }
 
@@ -0,0 +1,153 @@
+//! Turrets, which turn their weapons toward a target without turning the whole ship.
+
+use bevy::{ecs::query::QueryData, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    game::GameTimeDelta,
+    math_util::{get_angle_difference, get_heading_to_point, get_intercept_point},
+    movement::Velocity,
+};
+
+use super::{tools::LeadTarget, Target};
+
+/// A child weapon mount that rotates toward its [Target].
+///
+/// The turret's transform is rotated relative to its parent, within the traverse arc either side of its rest angle.
+#[derive(Component, Clone, Copy)]
+pub struct Turret {
+    /// Facing of the turret when centred, relative to its parent, in radians.
+    pub rest_angle: f32,
+    /// Furthest the turret can turn either side of its rest angle, in radians.
+    pub traverse: f32,
+    /// Maximum rotation speed, in radians per second.
+    pub rotation_speed: f32,
+    /// Current facing of the turret, relative to its rest angle, in radians.
+    pub angle: f32,
+}
+
+impl Turret {
+    pub fn new(rest_angle: f32, traverse: f32, rotation_speed: f32) -> Self {
+        Turret {
+            rest_angle,
+            traverse,
+            rotation_speed,
+            angle: 0.0,
+        }
+    }
+}
+
+/// The traverse arc and rotation speed of a turret, as written in templates.
+#[derive(Deserialize, Clone, Copy)]
+pub struct TurretMount {
+    pub traverse: f32,
+    pub rotation_speed: f32,
+}
+
+impl TurretMount {
+    /// Creates a turret centred on the given rest angle.
+    pub fn turret(&self, rest_angle: f32) -> Turret {
+        Turret::new(rest_angle, self.traverse, self.rotation_speed)
+    }
+}
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct TurretQuery {
+    turret: &'static mut Turret,
+    transform: &'static mut Transform,
+    global_transform: &'static GlobalTransform,
+    target: &'static Target,
+    parent: &'static Parent,
+    lead: Option<&'static LeadTarget>,
+}
+
+/// Rotates turrets toward their targets, or back to rest when they have none.
+pub fn rotate_turrets(
+    dt: Res<GameTimeDelta>,
+    mut query: Query<TurretQuery>,
+    parent_query: Query<&GlobalTransform>,
+    target_query: Query<(&GlobalTransform, Option<&Velocity>)>,
+) {
+    for mut turret in query.iter_mut() {
+        let Ok(parent_transform) = parent_query.get(turret.parent.get()) else {
+            continue;
+        };
+        let hull_heading = get_heading_to_point(parent_transform.up());
+        let position = turret.global_transform.translation();
+
+        let desired_angle = match turret
+            .target
+            .0
+            .and_then(|target| target_query.get(target).ok())
+        {
+            Some((target_transform, target_velocity)) => {
+                let mut aim_point = target_transform.translation();
+                if let (Some(lead), Some(velocity)) = (turret.lead, target_velocity) {
+                    if let Some(intercept) =
+                        get_intercept_point(position, aim_point, velocity.0, lead.projectile_speed)
+                    {
+                        aim_point = intercept;
+                    }
+                }
+                let heading_to_target = get_heading_to_point(aim_point - position);
+                get_angle_difference(heading_to_target, hull_heading + turret.turret.rest_angle)
+                    .clamp(-turret.turret.traverse, turret.turret.traverse)
+            }
+            None => 0.0,
+        };
+
+        let max_step = turret.turret.rotation_speed * dt.0;
+        let step = (desired_angle - turret.turret.angle).clamp(-max_step, max_step);
+        turret.turret.angle += step;
+        turret.transform.rotation =
+            Quat::from_rotation_z(turret.turret.rest_angle + turret.turret.angle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turret_turns_within_traverse() {
+        let mut app = App::new();
+        app.insert_resource(GameTimeDelta(0.5))
+            .add_systems(Update, rotate_turrets);
+        let enemy = app
+            .world
+            .spawn(GlobalTransform::from_xyz(100.0, 0.0, 0.0))
+            .id();
+        let turret = app
+            .world
+            .spawn((
+                Turret::new(0.0, 0.4, 0.5),
+                Transform::default(),
+                GlobalTransform::default(),
+                Target(Some(enemy)),
+            ))
+            .id();
+        app.world
+            .spawn(GlobalTransform::default())
+            .push_children(&[turret]);
+        let step = |app: &mut App| {
+            app.update();
+            app.world.get::<Turret>(turret).unwrap().angle
+        };
+
+        // the enemy is a quarter turn to the right, so the turret turns at its rotation speed until it hits the
+        // edge of its traverse
+        assert_eq!(step(&mut app), -0.25);
+        assert_eq!(step(&mut app), -0.4);
+        assert_eq!(step(&mut app), -0.4);
+        assert_eq!(
+            app.world.get::<Transform>(turret).unwrap().rotation,
+            Quat::from_rotation_z(-0.4)
+        );
+
+        // without a target it returns to rest
+        app.world.get_mut::<Target>(turret).unwrap().0 = None;
+        assert!((step(&mut app) + 0.15).abs() < 1e-6);
+        assert_eq!(step(&mut app), 0.0);
+    }
+}
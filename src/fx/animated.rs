@@ -1,13 +1,18 @@
 //! Helper functions for creating fire-and-forget special effect animations like explosions and bullet flares.
 
+#[cfg(feature = "graphics")]
 use std::time::Duration;
 
 use bevy::prelude::*;
+use serde::Deserialize;
 
+#[cfg(feature = "graphics")]
 use crate::game::GameTimeDelta;
 
-use super::beams::BeamTracking;
+#[cfg(feature = "graphics")]
+use super::beams::{BeamTracking, SustainedBeamSprite};
 
+#[cfg(feature = "graphics")]
 #[derive(Resource)]
 struct AnimatedEffectPrefabs {
     small_explosion: AnimatedEffectData,
@@ -22,6 +27,7 @@ struct AnimatedEffectPrefabs {
     big_flash_explosion: AnimatedEffectData
 }
 
+#[cfg(feature = "graphics")]
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
@@ -144,8 +150,10 @@ fn setup(
     commands.insert_resource(resources);
 }
 
+#[cfg(feature = "graphics")]
 pub struct AnimatedEffectsPlugin;
 
+#[cfg(feature = "graphics")]
 impl Plugin for AnimatedEffectsPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Update, (create_animated, update_animated));
@@ -176,7 +184,7 @@ impl AnimatedEffect {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Deserialize)]
 pub enum AnimatedEffects {
     SmallExplosion,
     MuzzleFlare,
@@ -190,12 +198,14 @@ pub enum AnimatedEffects {
     BigFlashExplosion
 }
 
+#[cfg(feature = "graphics")]
 struct AnimatedEffectData {
     atlas: Handle<TextureAtlasLayout>,
     texture: Handle<Image>,
     frame_time: f32,
 }
 
+#[cfg(feature = "graphics")]
 impl AnimatedEffectData {
     pub fn new(texture : Handle<Image>, atlas: Handle<TextureAtlasLayout>, frame_time: f32) -> Self {
         AnimatedEffectData { texture, atlas, frame_time }
@@ -205,7 +215,8 @@ impl AnimatedEffectData {
 #[derive(Component, Deref, DerefMut)]
 pub struct AnimationTimer(pub Timer);
 
-fn update_animated(
+#[cfg(feature = "graphics")]
+pub fn update_animated(
     mut commands: Commands,
     time: Res<GameTimeDelta>,
     texture_atlases: Res<Assets<TextureAtlasLayout>>,
@@ -235,11 +246,12 @@ fn update_animated(
     }
 }
 
+#[cfg(feature = "graphics")]
 fn create_animated(
     mut commands: Commands,
     prefabs: Res<AnimatedEffectPrefabs>,
     query: Query<(Entity, &CreateAnimatedEffect)>,
-    beam_track_query: Query<&BeamTracking>,
+    beam_track_query: Query<(&BeamTracking, Option<&SustainedBeamSprite>)>,
 ) {
     for (entity, effect) in query.iter() {
         // despawn the creation command
@@ -279,8 +291,11 @@ fn create_animated(
         }
 
         // hacky for now - add beam tracking if it exists
-        if let Ok(beam_tracking) = beam_track_query.get(entity) {
+        if let Ok((beam_tracking, sustained)) = beam_track_query.get(entity) {
             commands.entity(spawned).insert(*beam_tracking);
+            if let Some(sustained) = sustained {
+                commands.entity(spawned).insert(*sustained);
+            }
         }
     }
 }
@@ -1,13 +1,20 @@
 //! Illustrate laser beam attacks.
+//!
+//! Each pulse of a laser draws its own short-lived beam. A [SustainedBeam] draws a single beam when it locks on, which
+//! keeps animating and tracking its target until the beam ends.
 
 use bevy::prelude::*;
+use serde::Deserialize;
 
 use crate::combat::{
     attack::{Attack, AttackResult},
+    beams::SustainedBeam,
     effects::{EffectLocation, Instigator, SourceTransform},
     CombatSystems, Target,
 };
 
+#[cfg(feature = "graphics")]
+use super::animated::{update_animated, AnimatedEffect};
 use super::animated::{AnimatedEffects, CreateAnimatedEffect};
 
 pub struct BeamEffectPlugin;
@@ -18,6 +25,8 @@ impl Plugin for BeamEffectPlugin {
             FixedUpdate,
             (spawn_beams, beams_track_target).after(CombatSystems),
         );
+        #[cfg(feature = "graphics")]
+        app.add_systems(Update, hold_sustained_beams.before(update_animated));
     }
 }
 
@@ -34,33 +43,73 @@ fn get_transform(start: Vec3, end: Vec3, width: f32) -> Transform {
         * Transform::from_scale(scale)
 }
 
+/// An effect drawn as a beam. Effects without an attack, such as repairs, always reach their target.
+type BeamEffect<'a> = (
+    &'a BeamStyle,
+    &'a SourceTransform,
+    &'a EffectLocation,
+    &'a Target,
+    &'a Instigator,
+    Option<&'a Attack>,
+);
+
 fn spawn_beams(
     mut commands: Commands,
-    query: Query<(
-        &BeamStyle,
-        &SourceTransform,
-        &EffectLocation,
-        &Target,
-        &Instigator,
-        &Attack,
-    )>,
+    query: Query<BeamEffect>,
+    sustained_query: Query<&SustainedBeam>,
 ) {
     for (style, source, effect, target, instigator, attack) in query.iter() {
+        // Sustained beams are drawn once, when they lock on.
+        let sustained = sustained_query.get(instigator.0).ok();
+        if sustained.is_some_and(|beam| beam.elapsed > 0.0) {
+            continue;
+        }
+
         let transform = get_transform(source.0.translation(), effect.0, style.width);
-        commands
-            .spawn(CreateAnimatedEffect {
-                transform,
-                effect: style.effect,
-                parent: None,
-            })
-            .insert(BeamTracking {
-                target: target.0.expect("no target"),
-                source: instigator.0,
-                start: source.0.translation(),
-                end: effect.0,
-                width: style.width,
-                track_target: attack.result == AttackResult::Hit,
-            });
+        let mut beam = commands.spawn(CreateAnimatedEffect {
+            transform,
+            effect: style.effect,
+            parent: None,
+        });
+        beam.insert(BeamTracking {
+            target: target.0.expect("no target"),
+            source: instigator.0,
+            start: source.0.translation(),
+            end: effect.0,
+            width: style.width,
+            track_target: sustained.is_some()
+                || attack.is_none_or(|attack| attack.result == AttackResult::Hit),
+        });
+        if sustained.is_some() {
+            beam.insert(SustainedBeamSprite { tool: instigator.0 });
+        }
+    }
+}
+
+/// Loops the animation of sustained beams while their tool holds the beam, and removes them once it ends.
+#[cfg(feature = "graphics")]
+fn hold_sustained_beams(
+    mut commands: Commands,
+    mut query: Query<(
+        Entity,
+        &SustainedBeamSprite,
+        &mut AnimatedEffect,
+        &mut TextureAtlas,
+    )>,
+    beam_query: Query<&SustainedBeam>,
+) {
+    for (entity, sprite, mut effect, mut atlas) in query.iter_mut() {
+        if !beam_query
+            .get(sprite.tool)
+            .is_ok_and(SustainedBeam::is_firing)
+        {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        if effect.finished {
+            effect.finished = false;
+            atlas.index = 0;
+        }
     }
 }
 
@@ -81,7 +130,7 @@ fn beams_track_target(
     }
 }
 
-#[derive(Component)]
+#[derive(Component, Clone, Copy, Deserialize)]
 pub struct BeamStyle {
     pub effect: AnimatedEffects,
     pub width: f32,
@@ -96,3 +145,9 @@ pub struct BeamTracking {
     pub width: f32,
     pub track_target: bool,
 }
+
+/// The beam drawn for a [SustainedBeam] fired by the tool.
+#[derive(Clone, Copy, Component)]
+pub struct SustainedBeamSprite {
+    pub tool: Entity,
+}
@@ -1,19 +1,17 @@
 use bevy::prelude::*;
 
-use crate::{combat::damage::LastDamageTimer, materials::ShipMaterial, game::GameTimeDelta};
+use crate::{combat::damage::LastDamageTimer, materials::ShipMaterial};
 
 pub fn update_damage_flashes(
-    dt: Res<GameTimeDelta>,
     mut materials : ResMut<Assets<ShipMaterial>>,
-    mut query: Query<(&mut LastDamageTimer, &Handle<ShipMaterial>)>,
+    query: Query<(&LastDamageTimer, &Handle<ShipMaterial>)>,
 ) {
-    for (mut timer, material) in query.iter_mut() {
+    for (timer, material) in query.iter() {
         match materials.get_mut(material) {
             None => {}
             Some(material_instance) => {
                 material_instance.last_damaged_time = timer.0;
             }
         }
-        timer.0 += dt.0;
     }
 }
\ No newline at end of file
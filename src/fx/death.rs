@@ -3,7 +3,7 @@
 use bevy::prelude::*;
 use rand::Rng;
 
-use crate::{game::GameTimeDelta, combat::mortal::Dieing};
+use crate::{game::{rng::FxRng, GameTimeDelta}, combat::mortal::Dieing};
 
 use super::animated::{AnimatedEffects, CreateAnimatedEffect};
 
@@ -27,9 +27,9 @@ pub struct DeathEffect {
 pub fn do_death_effects(
     mut commands: Commands,
     dt: Res<GameTimeDelta>,
-    mut query: Query<(&mut DeathEffect, &GlobalTransform, &Dieing)>
+    mut query: Query<(&mut DeathEffect, &GlobalTransform, &Dieing)>,
+    mut rng: ResMut<FxRng>,
 ) {
-    let mut rng = rand::thread_rng();
     for (mut death_effect, transform, dieing) in query.iter_mut() {
         death_effect.time_to_explosion -= dt.0;
         death_effect.time_to_smoke -= dt.0;
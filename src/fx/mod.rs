@@ -2,22 +2,28 @@
 
 pub mod animated;
 pub mod beams;
+#[cfg(feature = "graphics")]
 pub mod damage_flash;
 pub mod death;
 
 use bevy::prelude::*;
 use rand::Rng;
+use serde::Deserialize;
 
-use crate::combat::{
-    attack::{Attack, AttackResult},
-    effects::EffectLocation,
-    CombatSystems,
+use crate::{
+    combat::{
+        attack::{Attack, AttackResult},
+        effects::EffectLocation,
+        shields::{Shield, ShieldEvent},
+        CombatSystems,
+    },
+    game::rng::FxRng,
 };
 
 use self::animated::AnimatedEffects;
 
 /// An effect that spawns when an effect hits a target.
-#[derive(Component)]
+#[derive(Component, Clone, Copy, Deserialize)]
 pub struct HitEffect {
     pub effect: AnimatedEffects,
 }
@@ -30,19 +36,20 @@ impl Plugin for EffectsPlugin {
             FixedUpdate,
             (
                 create_hit_effects.after(CombatSystems),
+                create_shield_effects.after(CombatSystems),
                 death::do_death_effects.after(crate::combat::mortal::update_dieing),
-                damage_flash::update_damage_flashes,
             ),
         );
+        #[cfg(feature = "graphics")]
+        app.add_systems(FixedUpdate, damage_flash::update_damage_flashes);
     }
 }
 
 fn create_hit_effects(
     mut commands: Commands,
     query: Query<(&HitEffect, &EffectLocation, &Attack)>,
+    mut rng: ResMut<FxRng>,
 ) {
-    let mut rng = rand::thread_rng();
-
     for (effect, location, attack) in query.iter() {
         if attack.result == AttackResult::Miss {
             continue;
@@ -57,3 +64,26 @@ fn create_hit_effects(
         });
     }
 }
+
+fn create_shield_effects(
+    mut commands: Commands,
+    mut events: EventReader<ShieldEvent>,
+    query: Query<(&GlobalTransform, &Shield)>,
+) {
+    for event in events.read() {
+        let (entity, effect) = match *event {
+            ShieldEvent::Collapsed(entity) => (entity, AnimatedEffects::FlashExplosion),
+            ShieldEvent::Rebooted(entity) => (entity, AnimatedEffects::Shield),
+            ShieldEvent::Recharged(_) => continue,
+        };
+        let Ok((transform, shield)) = query.get(entity) else {
+            continue;
+        };
+        commands.spawn(animated::CreateAnimatedEffect {
+            effect,
+            transform: Transform::from_translation(transform.translation())
+                * Transform::from_scale(Vec3::splat(shield.radius / 32.0)),
+            parent: None,
+        });
+    }
+}
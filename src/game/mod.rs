@@ -1,15 +1,25 @@
-use bevy::prelude::*;
+use bevy::{ecs::schedule::ExecutorKind, prelude::*};
 
 use crate::constants::FIXED_TIME_STEP;
 
+pub mod rng;
+
 #[derive(Resource)]
 pub struct GameTimeDelta(pub f32);
 
 #[derive(Resource)]
 pub struct GameSpeed(pub i32);
 
+/// Number of simulation steps completed so far.
+#[derive(Resource, Default)]
+pub struct SimulationTick(pub u64);
+
 pub static DESPAWN_STAGE: &str = "despawn_stage";
 
+/// Counts the simulation steps. The [SimulationTick] stops while this set is not run, e.g. while a battle is set up.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub struct SimulationTickSystems;
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum DespawnSet {
     Parallel,
@@ -27,10 +37,33 @@ pub struct BaseGamePlugin;
 
 impl Plugin for BaseGamePlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<SimulationTick>();
         app.add_systems(Startup,startup
         );
-        app.add_systems(Update, control_game_speed);
-        app.add_systems(Update, crate::materials::set_ship_shader_team_color);
+        app.add_systems(
+            Update,
+            control_game_speed.run_if(resource_exists::<ButtonInput<KeyCode>>),
+        );
+        app.add_systems(FixedFirst, update_game_time_delta);
+        app.add_systems(
+            FixedLast,
+            advance_simulation_tick.in_set(SimulationTickSystems),
+        );
+        #[cfg(feature = "graphics")]
+        app.add_systems(
+            Update,
+            crate::materials::set_ship_shader_team_color
+                .run_if(resource_exists::<Assets<crate::materials::ShipMaterial>>),
+        );
+
+        // Systems that draw from the gameplay rng must always run in the same order for a battle to be reproducible.
+        app.edit_schedule(FixedUpdate, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        });
+    }
+
+    fn finish(&self, app: &mut App) {
+        rng::insert_rng_resources(&mut app.world);
     }
 }
 
@@ -39,17 +72,23 @@ fn startup(mut commands: Commands) {
     commands.insert_resource(GameSpeed(2));
 }
 
-fn control_game_speed(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut dt: ResMut<GameTimeDelta>,
-    mut speed: ResMut<GameSpeed>,
-) {
+fn control_game_speed(keyboard_input: Res<ButtonInput<KeyCode>>, mut speed: ResMut<GameSpeed>) {
     if keyboard_input.just_pressed(KeyCode::Equal) {
         speed.0 += 1;
     }
     if keyboard_input.just_pressed(KeyCode::Minus) {
         speed.0 -= 1;
     }
-    speed.0 = speed.0.max(0).min(3);
+    speed.0 = speed.0.clamp(0, 3);
+}
+
+/// Sets the simulation time step from the game speed.
+///
+/// This runs at the start of each simulation step, so a change in speed always takes effect on a whole step.
+pub fn update_game_time_delta(speed: Res<GameSpeed>, mut dt: ResMut<GameTimeDelta>) {
     dt.0 = FIXED_TIME_STEP * speed.0 as f32 / 2.0;
 }
+
+fn advance_simulation_tick(mut tick: ResMut<SimulationTick>) {
+    tick.0 += 1;
+}
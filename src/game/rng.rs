@@ -0,0 +1,53 @@
+//! Seeded random number generation, so that the same seed and inputs always produce the same battle.
+
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+/// Seed used to initialise the simulation's random number generators.
+///
+/// Insert this resource before the app starts to reproduce a battle. If absent, a random seed is chosen.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimulationSeed(pub u64);
+
+/// Random numbers that can change the outcome of a battle, e.g. hit rolls.
+#[derive(Resource, Deref, DerefMut)]
+pub struct GameplayRng(pub ChaCha8Rng);
+
+/// Random numbers used only for cosmetic effects.
+///
+/// Kept separate from [GameplayRng] so that effects being drawn or not never alters the battle.
+#[derive(Resource, Deref, DerefMut)]
+pub struct FxRng(pub ChaCha8Rng);
+
+const GAMEPLAY_STREAM: u64 = 0;
+const FX_STREAM: u64 = 1;
+
+impl GameplayRng {
+    pub fn from_seed(seed: SimulationSeed) -> Self {
+        GameplayRng(seeded_stream(seed, GAMEPLAY_STREAM))
+    }
+}
+
+impl FxRng {
+    pub fn from_seed(seed: SimulationSeed) -> Self {
+        FxRng(seeded_stream(seed, FX_STREAM))
+    }
+}
+
+fn seeded_stream(seed: SimulationSeed, stream: u64) -> ChaCha8Rng {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed.0);
+    rng.set_stream(stream);
+    rng
+}
+
+/// Inserts the [SimulationSeed] and random number generators, choosing a seed if none was provided.
+pub fn insert_rng_resources(world: &mut World) {
+    let seed = match world.get_resource::<SimulationSeed>() {
+        Some(seed) => *seed,
+        None => SimulationSeed(rand::random()),
+    };
+    world.insert_resource(seed);
+    world.insert_resource(GameplayRng::from_seed(seed));
+    world.insert_resource(FxRng::from_seed(seed));
+}
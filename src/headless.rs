@@ -0,0 +1,88 @@
+//! Running battles without a window or GPU.
+//!
+//! Add [HeadlessCombatPlugins] alongside bevy's `MinimalPlugins` to simulate a battle with no meshes, textures or materials.
+//! Ship templates are still loaded from the asset folder. The app exits once a victory condition ends the battle:
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_combat::headless::HeadlessCombatPlugins;
+//!
+//! App::new()
+//!     .add_plugins((MinimalPlugins, HeadlessCombatPlugins))
+//!     .run();
+//! ```
+
+use bevy::{
+    app::{AppExit, PluginGroupBuilder},
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+
+use crate::{
+    ai::AIPlugin,
+    battle::{BattlePlugin, BattleState},
+    combat::CombatPlugin,
+    fx::animated::CreateAnimatedEffect,
+    game::BaseGamePlugin,
+    movement::MovementPlugin,
+    templates::{
+        ships::{
+            asset::ShipTemplatePlugin, rockets::RocketTemplatePlugin, shells::ShellTemplatePlugin,
+        },
+        weapons::asset::WeaponTemplatePlugin,
+    },
+};
+
+/// The simulation plugins, without any rendering.
+pub struct HeadlessCombatPlugins;
+
+impl PluginGroup for HeadlessCombatPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(TransformPlugin)
+            .add(HierarchyPlugin)
+            .add(AssetPlugin::default())
+            .add(HeadlessPlugin)
+            .add(BaseGamePlugin)
+            .add(BattlePlugin)
+            .add(MovementPlugin)
+            .add(AIPlugin)
+            .add(CombatPlugin)
+            .add(RocketTemplatePlugin)
+            .add(ShellTemplatePlugin)
+            .add(ShipTemplatePlugin)
+            .add(WeaponTemplatePlugin)
+    }
+}
+
+/// Steps the simulation as fast as possible, discards requests for visual effects and exits when the battle finishes.
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedPostUpdate, discard_animated_effects);
+        app.add_systems(OnEnter(BattleState::Finished), exit_when_finished);
+    }
+
+    fn finish(&self, app: &mut App) {
+        // Advance time by exactly one fixed step per update, rather than waiting on the wall clock.
+        if let Some(fixed) = app.world.get_resource::<Time<Fixed>>() {
+            let timestep = fixed.timestep();
+            app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+        }
+    }
+}
+
+/// Nothing draws animated effects when headless, so remove the requests before they accumulate.
+fn discard_animated_effects(
+    mut commands: Commands,
+    query: Query<Entity, With<CreateAnimatedEffect>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn exit_when_finished(mut exit_events: EventWriter<AppExit>) {
+    exit_events.send(AppExit);
+}
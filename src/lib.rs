@@ -1,4 +1,5 @@
 pub mod ai;
+pub mod battle;
 pub mod combat;
 pub mod constants;
 pub mod math_util;
@@ -6,4 +7,10 @@ pub mod movement;
 pub mod game;
 pub mod templates;
 pub mod fx;
-pub mod materials;
\ No newline at end of file
+#[cfg(feature = "graphics")]
+pub mod materials;
+pub mod headless;
+pub mod replay;
+pub mod report;
+pub mod scenario;
+pub mod spatial;
\ No newline at end of file
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::path::{Path, PathBuf};
 
 use bevy::{
     asset::AssetMetaCheck,
@@ -8,19 +8,18 @@ use bevy::{
     winit::{UpdateMode, WinitSettings},
 };
 
+use bevy_combat::{ai::AIPlugin, materials::ShipMaterial};
 use bevy_combat::{
-    ai::AIPlugin, game::GameTimeDelta, materials::ShipMaterial,
-    templates::ships::frigates::RocketFrigateSpawner,
+    battle::BattlePlugin,
+    game::BaseGamePlugin,
+    movement::*,
+    replay::{Replay, ReplayPlugin},
+    report::BattleReportPlugin,
+    scenario::ScenarioPlugin,
 };
-use bevy_combat::{
-    combat::Team,
-    templates::ships::{
-        fighters::{DroneSpawner, SmallShipSpawner},
-        spawn::SpawnBundle,
-    },
-};
-use bevy_combat::{game::BaseGamePlugin, movement::*};
-use rand::Rng;
+
+/// Asset path of the scenario played when none is given on the command line.
+const DEFAULT_SCENARIO: &str = "scenarios/default.scenario.ron";
 
 #[derive(Component)]
 pub struct PrintTimer(Timer);
@@ -50,6 +49,7 @@ fn main() {
     app.add_plugins(bevy_wasm_window_resize::WindowResizePlugin);
     app.add_plugins((
         BaseGamePlugin,
+        BattlePlugin,
         AIPlugin,
         MovementPlugin,
         bevy_combat::combat::CombatPlugin,
@@ -57,9 +57,10 @@ fn main() {
         bevy_combat::fx::EffectsPlugin,
         bevy_combat::fx::beams::BeamEffectPlugin,
         Material2dPlugin::<ShipMaterial>::default(),
-        bevy_combat::templates::ships::fighters::FighterTemplatePlugin,
-        bevy_combat::templates::ships::frigates::FrigateTemplatePlugin,
         bevy_combat::templates::ships::rockets::RocketTemplatePlugin,
+        bevy_combat::templates::ships::shells::ShellTemplatePlugin,
+        bevy_combat::templates::ships::asset::ShipTemplatePlugin,
+        bevy_combat::templates::weapons::asset::WeaponTemplatePlugin,
     ));
 
     app.insert_resource(WinitSettings {
@@ -67,152 +68,71 @@ fn main() {
         unfocused_mode: UpdateMode::Continuous,
     });
 
+    if let Some(replay) = replay_plugin_from_args() {
+        app.add_plugins(replay);
+    }
+    if let Some(report) = report_plugin_from_args() {
+        app.add_plugins(report);
+    }
+
+    app.add_plugins(scenario_from_args());
+
     app.add_systems(Startup, setup);
     app.add_systems(Update, tick);
-    app.add_systems(FixedUpdate, spawn_reinforcements);
-    app.insert_resource(WaveTimer(Timer::from_seconds(15.0, TimerMode::Repeating)));
     app.run()
 }
 
-fn setup(mut commands: Commands) {
-    let mut rng = rand::thread_rng();
-
-    let tile_size = Vec2::splat(16.0);
-
-    commands
-        .spawn(Camera2dBundle::default())
-        .insert(PrintTimer(Timer::from_seconds(1.0, TimerMode::Repeating)));
-
-    commands.insert_resource(ClearColor(Color::rgb(0.8, 0.8, 0.8)));
-
-    // Team 1
-    for _i in 0..20 {
-        let position =
-            Vec2::new(-20.0, 0.0) + Vec2::new(rng.gen_range(-5.0..5.0), rng.gen_range(-20.0..20.0));
-        let translation = (position * tile_size).extend(0.0);
-        let rotation = Quat::from_rotation_z(rng.gen::<f32>());
-        let scale = Vec3::splat(0.5);
-
-        commands.spawn(SpawnBundle {
-            spawn: SmallShipSpawner,
-            transform: Transform {
-                translation,
-                rotation,
-                scale,
-            },
-            team: Team(1),
-        });
-    }
-
-    // Team 2
-    for _i in 0..60 {
-        let drone_size = Vec2::splat(8.0);
-        let position =
-            Vec2::new(60.0, 0.0) + Vec2::new(rng.gen_range(-5.0..5.0), rng.gen_range(-20.0..20.0));
-        let translation = (position * drone_size).extend(0.0);
-        let rotation = Quat::from_rotation_z(rng.gen::<f32>());
-        let scale = Vec3::splat(0.5);
-
-        commands.spawn(SpawnBundle {
-            spawn: DroneSpawner,
-            transform: Transform {
-                translation,
-                rotation,
-                scale,
-            },
-            team: Team(2),
-        });
+/// Reads `--record <path>` or `--replay <path>` from the command line.
+fn replay_plugin_from_args() -> Option<ReplayPlugin> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--record" => return args.next().map(|path| ReplayPlugin::Record(PathBuf::from(path))),
+            "--replay" => return args.next().map(|path| ReplayPlugin::Playback(PathBuf::from(path))),
+            _ => {}
+        }
     }
+    None
 }
 
-#[derive(Resource)]
-struct WaveTimer(Timer);
-
-fn spawn_reinforcements(
-    mut wave_timer: ResMut<WaveTimer>,
-    team_members: Query<&Team>,
-    dt: Res<GameTimeDelta>,
-    mut commands: Commands,
-) {
-    wave_timer.0.tick(Duration::from_secs_f32(dt.0));
-
-    if wave_timer.0.finished() {
-        let mut team_1_count = 0;
-        let mut team_2_count = 0;
-
-        for team_member in team_members.iter() {
-            match team_member.0 {
-                1 => team_1_count += 1,
-                2 => team_2_count += 2,
-                _ => {}
-            }
+/// Reads `--report <path>` from the command line.
+fn report_plugin_from_args() -> Option<BattleReportPlugin> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--report" {
+            return args.next().map(|path| BattleReportPlugin(PathBuf::from(path)));
         }
+    }
+    None
+}
 
-        let reinforced_team = if team_1_count > team_2_count {
-            Team(2)
-        } else {
-            Team(1)
-        };
-
-        let mut rng = rand::thread_rng();
-        let number = rng.gen_range(10..=14);
-        let drones = rng.gen_bool(0.5);
-        for _i in 0..number {
-            let pos: Vec2 = get_random_spawn_position_for_team(&reinforced_team);
-            let translation = (pos).extend(0.0);
-            let rotation = Quat::from_rotation_z(rng.gen::<f32>());
-            let scale = Vec3::splat(0.5);
-
-            if drones {
-                commands.spawn(SpawnBundle {
-                    spawn: DroneSpawner,
-                    transform: Transform {
-                        translation,
-                        rotation,
-                        scale,
-                    },
-                    team: reinforced_team,
-                });
-            } else {
-                commands.spawn(SpawnBundle {
-                    spawn: SmallShipSpawner,
-                    transform: Transform {
-                        translation,
-                        rotation,
-                        scale,
-                    },
-                    team: reinforced_team,
-                });
+/// Plays the scenario asset at `--scenario <path>` on the command line, the scenario a replay given with
+/// `--replay <path>` was recorded in, or the default scenario.
+fn scenario_from_args() -> ScenarioPlugin {
+    let mut scenario_path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--scenario" => scenario_path = args.next(),
+            "--replay" if scenario_path.is_none() => {
+                scenario_path = args
+                    .next()
+                    .and_then(|path| Replay::load(Path::new(&path)).ok())
+                    .and_then(|replay| replay.scenario);
             }
-        }
-        for _i in 0..rng.gen_range(0..=2) {
-            let pos: Vec2 = get_random_spawn_position_for_team(&reinforced_team);
-            let translation = (pos).extend(0.0);
-            let rotation = Quat::from_rotation_z(rng.gen::<f32>());
-            let scale = Vec3::splat(0.5);
-
-            commands.spawn(SpawnBundle {
-                spawn: RocketFrigateSpawner,
-                transform: Transform {
-                    translation,
-                    rotation,
-                    scale,
-                },
-                team: reinforced_team,
-            });
+            _ => {}
         }
     }
+
+    ScenarioPlugin::Load(scenario_path.unwrap_or_else(|| DEFAULT_SCENARIO.to_string()))
 }
 
-fn get_random_spawn_position_for_team(team: &Team) -> Vec2 {
-    let mut rng = rand::thread_rng();
-    if team.0 == 1 {
-        8.0 * (Vec2::new(-80.0, 0.0)
-            + Vec2::new(rng.gen_range(-5.0..5.0), rng.gen_range(-20.0..20.0)))
-    } else {
-        8.0 * (Vec2::new(80.0, 0.0)
-            + Vec2::new(rng.gen_range(-5.0..5.0), rng.gen_range(-20.0..20.0)))
-    }
+fn setup(mut commands: Commands) {
+    commands
+        .spawn(Camera2dBundle::default())
+        .insert(PrintTimer(Timer::from_seconds(1.0, TimerMode::Repeating)));
+
+    commands.insert_resource(ClearColor(Color::rgb(0.8, 0.8, 0.8)));
 }
 
 fn tick(
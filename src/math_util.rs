@@ -25,6 +25,54 @@ pub fn get_angle_difference(target: f32, initial: f32) -> f32 {
     (a - (a / n).floor() * n) - std::f32::consts::PI
 }
 
+/// Gets the point where a projectile fired now meets a target moving at a constant velocity.
+///
+/// Returns `None` if the projectile is too slow to ever reach the target.
+///
+/// # Arguments
+///
+/// * `source`: position the projectile is fired from.
+///
+/// * `target`: current position of the target.
+///
+/// * `target_velocity`: velocity of the target.
+///
+/// * `projectile_speed`: speed of the projectile.
+pub fn get_intercept_point(
+    source: Vec3,
+    target: Vec3,
+    target_velocity: Vec3,
+    projectile_speed: f32,
+) -> Option<Vec3> {
+    // Solve |delta + target_velocity * t| = projectile_speed * t for the earliest positive time t.
+    let delta = target - source;
+    let a = target_velocity.length_squared() - projectile_speed.powi(2);
+    let b = 2.0 * delta.dot(target_velocity);
+    let c = delta.length_squared();
+
+    let time = if a.abs() < 1.0e-6 {
+        if b >= 0.0 {
+            return None;
+        }
+        -c / b
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        let t1 = (-b - root) / (2.0 * a);
+        let t2 = (-b + root) / (2.0 * a);
+        match (t1 >= 0.0, t2 >= 0.0) {
+            (true, true) => t1.min(t2),
+            (true, false) => t1,
+            (false, true) => t2,
+            (false, false) => return None,
+        }
+    };
+    Some(target + target_velocity * time)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,4 +87,21 @@ mod tests {
         assert!(get_angle_difference(0.5, 2.0*pi-0.5) - 1.0 < 1.0e-4);
         assert!(get_angle_difference(2.0*pi-0.5, 0.5) - -1.0 < 1.0e-4);
     }
+
+    #[test]
+    fn test_intercept_point() {
+        // stationary target is hit where it is
+        let point = get_intercept_point(Vec3::ZERO, Vec3::new(100.0, 0.0, 0.0), Vec3::ZERO, 10.0).unwrap();
+        assert!((point - Vec3::new(100.0, 0.0, 0.0)).length() < 1.0e-3);
+
+        // crossing target: projectile and target arrive at the same time
+        let target = Vec3::new(100.0, 0.0, 0.0);
+        let velocity = Vec3::new(0.0, 30.0, 0.0);
+        let point = get_intercept_point(Vec3::ZERO, target, velocity, 50.0).unwrap();
+        let time = (point - target).length() / velocity.length();
+        assert!((point.length() / 50.0 - time).abs() < 1.0e-3);
+
+        // target fleeing faster than the projectile cannot be reached
+        assert!(get_intercept_point(Vec3::ZERO, target, Vec3::new(60.0, 0.0, 0.0), 50.0).is_none());
+    }
 }
\ No newline at end of file
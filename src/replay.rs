@@ -0,0 +1,314 @@
+//! Recording and playback of battles.
+//!
+//! A battle is deterministic for a given [SimulationSeed], so it can be reproduced from the seed, the spawn commands
+//! present when it started, the [Scenario] it was played in and every change to the [GameSpeed] made while it ran.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{app::AppExit, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    battle::Vip,
+    combat::{effects::Instigator, Team},
+    game::{
+        rng::{GameplayRng, SimulationSeed},
+        update_game_time_delta, GameSpeed, SimulationTick,
+    },
+    scenario::{start_scenario, PendingScenario, Scenario},
+    templates::ships::asset::TemplateSpawner,
+};
+
+/// Everything required to reproduce a battle.
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    /// Position in the gameplay rng stream when the first simulation step began, after any scenario had started.
+    pub rng_word_pos: u64,
+    /// Spawn commands present when the first simulation step began, after any scenario had started.
+    pub spawns: Vec<RecordedSpawn>,
+    /// Game speed changes, in the order they occurred.
+    pub speed_changes: Vec<SpeedChange>,
+    /// Number of simulation steps recorded.
+    pub length: u64,
+    /// The asset path of the [Scenario] the battle was played in, if it was loaded from one.
+    #[serde(default)]
+    pub scenario: Option<String>,
+}
+
+/// A spawn command recorded in a [Replay].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecordedSpawn {
+    /// The asset path of the [ShipTemplate](crate::templates::ships::asset::ShipTemplate).
+    pub template: String,
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub team: Option<i32>,
+    #[serde(default)]
+    pub vip: bool,
+}
+
+/// The game speed was set at the start of a simulation step.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct SpeedChange {
+    pub tick: u64,
+    pub speed: i32,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Write(ron::Error),
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "replay io error: {}", err),
+            ReplayError::Parse(err) => write!(f, "could not parse replay: {}", err),
+            ReplayError::Write(err) => write!(f, "could not write replay: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        let text = fs::read_to_string(path).map_err(ReplayError::Io)?;
+        ron::from_str(&text).map_err(ReplayError::Parse)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        let text = ron::to_string(self).map_err(ReplayError::Write)?;
+        fs::write(path, text).map_err(ReplayError::Io)
+    }
+}
+
+/// A battle being recorded.
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    pub replay: Replay,
+    pub path: PathBuf,
+}
+
+/// A battle being played back.
+#[derive(Resource)]
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    next_speed_change: usize,
+    finished: bool,
+}
+
+/// Run condition that is true while a replay is being played back.
+pub fn is_playing_back(player: Option<Res<ReplayPlayer>>) -> bool {
+    player.is_some()
+}
+
+pub enum ReplayPlugin {
+    /// Record the battle, writing the replay to the path when the app exits.
+    Record(PathBuf),
+    /// Play back the replay at the path.
+    Playback(PathBuf),
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        match self {
+            ReplayPlugin::Record(path) => {
+                app.insert_resource(ReplayRecorder {
+                    replay: Replay::default(),
+                    path: path.clone(),
+                });
+                app.add_systems(
+                    FixedFirst,
+                    (record_battle_start, record_spawn_commands)
+                        .chain()
+                        .after(start_scenario)
+                        .run_if(
+                            is_first_tick
+                                .and_then(not(resource_exists::<PendingScenario>))
+                                .and_then(run_once()),
+                        ),
+                );
+                app.add_systems(
+                    FixedFirst,
+                    record_speed_changes.before(update_game_time_delta),
+                );
+                app.add_systems(Last, save_replay_on_exit);
+            }
+            ReplayPlugin::Playback(path) => {
+                let replay = match Replay::load(path) {
+                    Ok(replay) => replay,
+                    Err(err) => {
+                        error!("Failed to load replay {:?}: {}", path, err);
+                        return;
+                    }
+                };
+                app.insert_resource(SimulationSeed(replay.seed));
+                app.insert_resource(ReplayPlayer {
+                    replay,
+                    next_speed_change: 0,
+                    finished: false,
+                });
+                app.add_systems(
+                    FixedFirst,
+                    (play_battle_start, spawn_recorded)
+                        .chain()
+                        .run_if(is_first_tick.and_then(run_once())),
+                );
+                app.add_systems(
+                    FixedFirst,
+                    play_speed_changes.before(update_game_time_delta),
+                );
+            }
+        }
+    }
+}
+
+/// The first tick lasts while a battle is being set up, so systems that start the replay must also run only once.
+fn is_first_tick(tick: Res<SimulationTick>) -> bool {
+    tick.0 == 0
+}
+
+fn record_battle_start(
+    mut recorder: ResMut<ReplayRecorder>,
+    seed: Res<SimulationSeed>,
+    rng: Res<GameplayRng>,
+    scenario: Option<Res<Scenario>>,
+) {
+    recorder.replay.seed = seed.0;
+    recorder.replay.rng_word_pos = rng.get_word_pos() as u64;
+    recorder.replay.scenario = scenario.and_then(|scenario| scenario.path.clone());
+}
+
+/// The parts of a spawn command written to a [RecordedSpawn].
+type SpawnCommandRecord<'a> = (
+    &'a TemplateSpawner,
+    &'a Transform,
+    Option<&'a Team>,
+    Has<Vip>,
+);
+
+impl RecordedSpawn {
+    fn new(template: String, transform: &Transform, team: Option<&Team>, vip: bool) -> Self {
+        RecordedSpawn {
+            template,
+            translation: transform.translation.to_array(),
+            rotation: transform.rotation.to_array(),
+            team: team.map(|team| team.0),
+            vip,
+        }
+    }
+
+    fn spawn(&self, commands: &mut Commands, asset_server: &AssetServer) {
+        let mut entity = commands.spawn((
+            TemplateSpawner(asset_server.load(self.template.clone())),
+            Transform {
+                translation: Vec3::from_array(self.translation),
+                rotation: Quat::from_array(self.rotation),
+                scale: Vec3::splat(0.5),
+            },
+        ));
+        if let Some(team) = self.team {
+            entity.insert(Team(team));
+        }
+        if self.vip {
+            entity.insert(Vip);
+        }
+    }
+}
+
+/// Records spawn commands that were not created by another entity, e.g. a ship launched by a carrier.
+fn record_spawn_commands(
+    mut recorder: ResMut<ReplayRecorder>,
+    query: Query<SpawnCommandRecord, Without<Instigator>>,
+) {
+    for (spawner, transform, team, vip) in query.iter() {
+        let Some(path) = spawner.0.path() else {
+            warn!("Ship templates without a path cannot be recorded");
+            continue;
+        };
+        recorder.replay.spawns.push(RecordedSpawn::new(
+            path.to_string(),
+            transform,
+            team,
+            vip,
+        ));
+    }
+}
+
+fn record_speed_changes(
+    mut recorder: ResMut<ReplayRecorder>,
+    speed: Res<GameSpeed>,
+    tick: Res<SimulationTick>,
+) {
+    let replay = &mut recorder.replay;
+    replay.length = tick.0 + 1;
+    if replay.speed_changes.last().map(|change| change.speed) != Some(speed.0) {
+        replay.speed_changes.push(SpeedChange {
+            tick: tick.0,
+            speed: speed.0,
+        });
+    }
+}
+
+fn save_replay_on_exit(mut exit_events: EventReader<AppExit>, recorder: Res<ReplayRecorder>) {
+    if exit_events.read().next().is_none() {
+        return;
+    }
+    match recorder.replay.save(&recorder.path) {
+        Ok(()) => info!("Saved replay to {:?}", recorder.path),
+        Err(err) => error!("Failed to save replay to {:?}: {}", recorder.path, err),
+    }
+}
+
+fn play_battle_start(player: Res<ReplayPlayer>, mut rng: ResMut<GameplayRng>) {
+    rng.set_word_pos(player.replay.rng_word_pos as u128);
+}
+
+fn spawn_recorded(
+    mut commands: Commands,
+    player: Res<ReplayPlayer>,
+    asset_server: Res<AssetServer>,
+) {
+    for spawn in player.replay.spawns.iter() {
+        spawn.spawn(&mut commands, &asset_server);
+    }
+}
+
+fn play_speed_changes(
+    mut player: ResMut<ReplayPlayer>,
+    mut speed: ResMut<GameSpeed>,
+    tick: Res<SimulationTick>,
+) {
+    if player.finished {
+        speed.0 = 0;
+        return;
+    }
+    if tick.0 >= player.replay.length {
+        info!("Replay finished after {} steps", player.replay.length);
+        player.finished = true;
+        speed.0 = 0;
+        return;
+    }
+
+    while let Some(change) = player.replay.speed_changes.get(player.next_speed_change) {
+        if change.tick > tick.0 {
+            break;
+        }
+        player.next_speed_change += 1;
+    }
+    // Playback overrides any speed set from the keyboard.
+    if let Some(change) = player
+        .next_speed_change
+        .checked_sub(1)
+        .and_then(|index| player.replay.speed_changes.get(index))
+    {
+        speed.0 = change.speed;
+    }
+}
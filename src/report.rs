@@ -0,0 +1,435 @@
+//! Reports summarising the outcome of a battle, written to disk as JSON or CSV when the app exits.
+//!
+//! Reports are built from the [BattleStats] tallied during combat, along with the ships spawned, lost and still alive.
+//! When the [BattlePlugin](crate::battle::BattlePlugin) is running the battle, the report follows its [BattleClock]
+//! and [BattleResult].
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{app::AppExit, prelude::*, utils::HashMap};
+use serde::Serialize;
+
+use crate::{
+    ai::aggression::AgentCategory,
+    battle::{is_missile, BattleClock, BattleResult, BattleState, SurvivingShip},
+    combat::{
+        events::CombatEvent,
+        mortal::Health,
+        projectile::Projectile,
+        stats::{BattleStats, CombatStats},
+        Team,
+    },
+    game::GameTimeDelta,
+    templates::ships::spawn::TemplateName,
+};
+
+/// Interval between samples of team strength, in seconds of simulated time.
+pub const STRENGTH_SAMPLE_INTERVAL: f32 = 5.0;
+
+/// The outcome of a battle.
+#[derive(Serialize, Default, Clone, Debug, PartialEq)]
+pub struct BattleReport {
+    /// The winner of the [BattleResult] if the battle finished, otherwise the only team with ships left, if there is
+    /// one.
+    pub winner: Option<i32>,
+    /// Length of the battle, in seconds of simulated time.
+    pub duration: f32,
+    pub survivors: Vec<Survivors>,
+    pub strength_timeline: Vec<TeamStrength>,
+    pub templates: Vec<TemplateStats>,
+}
+
+/// Number of ships of a team left alive in a category.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Survivors {
+    pub team: i32,
+    pub category: String,
+    pub count: u32,
+}
+
+/// Ships and total hull health of a team at a point in the battle.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct TeamStrength {
+    pub time: f32,
+    pub team: i32,
+    pub ships: u32,
+    pub health: f32,
+}
+
+/// Combined statistics of all ships of a team spawned from one template.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct TemplateStats {
+    pub template: String,
+    pub team: i32,
+    pub spawned: u32,
+    pub lost: u32,
+    pub damage_dealt: f32,
+    pub damage_taken: f32,
+    pub shield_absorbed: f32,
+    pub shots_fired: u32,
+    pub hits: u32,
+    pub hit_rate: f32,
+    pub kills: u32,
+}
+
+#[derive(Debug)]
+pub enum ReportError {
+    Io(io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportError::Io(err) => write!(f, "report io error: {}", err),
+            ReportError::Json(err) => write!(f, "could not write report: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ReportError {}
+
+impl BattleReport {
+    /// Saves the report as CSV if the path ends in `.csv`, otherwise as JSON.
+    pub fn save(&self, path: &Path) -> Result<(), ReportError> {
+        let text = if path.extension().is_some_and(|extension| extension == "csv") {
+            self.to_csv()
+        } else {
+            serde_json::to_string_pretty(self).map_err(ReportError::Json)?
+        };
+        fs::write(path, text).map_err(ReportError::Io)
+    }
+
+    /// Writes the report as CSV, with one table per section separated by blank lines.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        // Writing to a string cannot fail.
+        let _ = self.write_csv(&mut csv);
+        csv
+    }
+
+    fn write_csv(&self, csv: &mut String) -> fmt::Result {
+        writeln!(csv, "winner,duration")?;
+        let winner = self.winner.map(|team| team.to_string()).unwrap_or_default();
+        writeln!(csv, "{},{}", winner, self.duration)?;
+
+        writeln!(csv)?;
+        writeln!(csv, "team,category,survivors")?;
+        for survivors in self.survivors.iter() {
+            writeln!(
+                csv,
+                "{},{},{}",
+                survivors.team, survivors.category, survivors.count
+            )?;
+        }
+
+        writeln!(csv)?;
+        writeln!(csv, "time,team,ships,health")?;
+        for strength in self.strength_timeline.iter() {
+            writeln!(
+                csv,
+                "{},{},{},{}",
+                strength.time, strength.team, strength.ships, strength.health
+            )?;
+        }
+
+        writeln!(csv)?;
+        writeln!(
+            csv,
+            "template,team,spawned,lost,damage_dealt,damage_taken,shield_absorbed,shots_fired,hits,hit_rate,kills"
+        )?;
+        for stats in self.templates.iter() {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{},{}",
+                stats.template,
+                stats.team,
+                stats.spawned,
+                stats.lost,
+                stats.damage_dealt,
+                stats.damage_taken,
+                stats.shield_absorbed,
+                stats.shots_fired,
+                stats.hits,
+                stats.hit_rate,
+                stats.kills
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Names the categories of a ship as they are written in ship templates, e.g. `FIGHTER`.
+fn category_name(category: AgentCategory) -> String {
+    let mut name = String::new();
+    let _ = bitflags::parser::to_writer(&category, &mut name);
+    name
+}
+
+/// A ship spawned during the battle.
+struct ShipRecord {
+    template: String,
+    team: i32,
+    lost: bool,
+}
+
+/// Gathers what happens over a battle, for the report written when the app exits.
+#[derive(Resource)]
+pub struct BattleReportRecorder {
+    pub path: PathBuf,
+    duration: f32,
+    next_sample: f32,
+    timeline: Vec<TeamStrength>,
+    ships: HashMap<Entity, ShipRecord>,
+}
+
+impl BattleReportRecorder {
+    pub fn new(path: PathBuf) -> Self {
+        BattleReportRecorder {
+            path,
+            duration: 0.0,
+            next_sample: 0.0,
+            timeline: Vec::new(),
+            ships: HashMap::new(),
+        }
+    }
+
+    /// Builds the report from the statistics of the battle, the `(team, category)` of each surviving ship and the
+    /// result, if the battle has finished.
+    pub fn report(
+        &self,
+        stats: &BattleStats,
+        survivors: &[(i32, AgentCategory)],
+        result: Option<&BattleResult>,
+    ) -> BattleReport {
+        let mut survivor_counts = BTreeMap::new();
+        for &(team, category) in survivors.iter() {
+            *survivor_counts
+                .entry((team, category_name(category)))
+                .or_insert(0) += 1;
+        }
+        let mut surviving_teams: Vec<_> = survivor_counts.keys().map(|(team, _)| *team).collect();
+        surviving_teams.dedup();
+
+        let mut templates = BTreeMap::new();
+        for (entity, ship) in self.ships.iter() {
+            let (spawned, lost, combined) = templates
+                .entry((ship.template.clone(), ship.team))
+                .or_insert((0, 0, CombatStats::default()));
+            *spawned += 1;
+            if ship.lost {
+                *lost += 1;
+            }
+            if let Some(ship_stats) = stats.ships.get(entity) {
+                combined.damage_dealt += ship_stats.damage_dealt;
+                combined.damage_taken += ship_stats.damage_taken;
+                combined.shield_absorbed += ship_stats.shield_absorbed;
+                combined.shots_fired += ship_stats.shots_fired;
+                combined.hits += ship_stats.hits;
+                combined.kills += ship_stats.kills;
+            }
+        }
+
+        let (winner, duration) = match result {
+            Some(result) => (result.winner, result.duration),
+            None => match surviving_teams.as_slice() {
+                [team] => (Some(*team), self.duration),
+                _ => (None, self.duration),
+            },
+        };
+
+        BattleReport {
+            winner,
+            duration,
+            survivors: survivor_counts
+                .into_iter()
+                .map(|((team, category), count)| Survivors {
+                    team,
+                    category,
+                    count,
+                })
+                .collect(),
+            strength_timeline: self.timeline.clone(),
+            templates: templates
+                .into_iter()
+                .map(|((template, team), (spawned, lost, stats))| TemplateStats {
+                    template,
+                    team,
+                    spawned,
+                    lost,
+                    damage_dealt: stats.damage_dealt,
+                    damage_taken: stats.damage_taken,
+                    shield_absorbed: stats.shield_absorbed,
+                    shots_fired: stats.shots_fired,
+                    hits: stats.hits,
+                    hit_rate: stats.hit_rate(),
+                    kills: stats.kills,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Writes a [BattleReport] to the path when the app exits.
+pub struct BattleReportPlugin(pub PathBuf);
+
+impl Plugin for BattleReportPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BattleReportRecorder::new(self.0.clone()));
+        app.add_systems(FixedPostUpdate, (record_ships, sample_team_strength));
+        app.add_systems(Last, save_report_on_exit);
+    }
+}
+
+type SurvivingShipQuery<'w, 's, 'a> =
+    Query<'w, 's, (&'a Team, &'a Health, &'a AgentCategory), SurvivingShip>;
+
+/// Records each ship spawned and whether it was lost. Missiles and shells are not ships.
+fn record_ships(
+    mut events: EventReader<CombatEvent>,
+    mut recorder: ResMut<BattleReportRecorder>,
+    query: Query<(&TemplateName, &Team), Without<Projectile>>,
+) {
+    for event in events.read() {
+        match *event {
+            CombatEvent::Spawned { entity, .. } => {
+                if let Ok((template, team)) = query.get(entity) {
+                    recorder.ships.insert(
+                        entity,
+                        ShipRecord {
+                            template: template.0.clone(),
+                            team: team.0,
+                            lost: false,
+                        },
+                    );
+                }
+            }
+            CombatEvent::Killed { entity, .. } => {
+                if let Some(ship) = recorder.ships.get_mut(&entity) {
+                    ship.lost = true;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn sample_team_strength(
+    dt: Res<GameTimeDelta>,
+    clock: Option<Res<BattleClock>>,
+    state: Option<Res<State<BattleState>>>,
+    mut recorder: ResMut<BattleReportRecorder>,
+    query: SurvivingShipQuery,
+) {
+    // Only the time the battle has been running counts, not setting up or after it has finished.
+    if state.is_some_and(|state| {
+        !matches!(state.get(), BattleState::Running | BattleState::Paused)
+    }) {
+        return;
+    }
+    recorder.duration = match clock {
+        Some(clock) => clock.0,
+        None => recorder.duration + dt.0,
+    };
+    if recorder.duration < recorder.next_sample {
+        return;
+    }
+    recorder.next_sample += STRENGTH_SAMPLE_INTERVAL;
+
+    let mut teams = BTreeMap::new();
+    for (team, health, _) in query
+        .iter()
+        .filter(|(_, _, category)| !is_missile(category))
+    {
+        let (ships, total_health) = teams.entry(team.0).or_insert((0, 0.0));
+        *ships += 1;
+        *total_health += health.0.max(0.0);
+    }
+    let time = recorder.duration;
+    recorder.timeline.extend(
+        teams
+            .into_iter()
+            .map(|(team, (ships, health))| TeamStrength {
+                time,
+                team,
+                ships,
+                health,
+            }),
+    );
+}
+
+fn save_report_on_exit(
+    mut exit_events: EventReader<AppExit>,
+    recorder: Res<BattleReportRecorder>,
+    stats: Res<BattleStats>,
+    result: Option<Res<BattleResult>>,
+    query: SurvivingShipQuery,
+) {
+    if exit_events.read().next().is_none() {
+        return;
+    }
+    let survivors: Vec<_> = query
+        .iter()
+        .filter(|(_, _, category)| !is_missile(category))
+        .map(|(team, _, category)| (team.0, *category))
+        .collect();
+    let report = recorder.report(&stats, &survivors, result.as_deref());
+    match report.save(&recorder.path) {
+        Ok(()) => info!("Saved battle report to {:?}", recorder.path),
+        Err(err) => error!(
+            "Failed to save battle report to {:?}: {}",
+            recorder.path, err
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::VictoryCondition;
+
+    #[test]
+    fn test_report_names_winner_and_combines_template_stats() {
+        let mut recorder = BattleReportRecorder::new(PathBuf::from("battle.csv"));
+        let mut stats = BattleStats::default();
+        let mut world = World::new();
+        for (team, lost, kills) in [(1, false, 2), (1, true, 1), (2, true, 0)] {
+            let ship = world.spawn_empty().id();
+            recorder.ships.insert(
+                ship,
+                ShipRecord {
+                    template: "drone".to_string(),
+                    team,
+                    lost,
+                },
+            );
+            stats.ships.insert(ship, CombatStats { kills, ..default() });
+        }
+
+        let survivors = [(1, AgentCategory::FIGHTER)];
+        let report = recorder.report(&stats, &survivors, None);
+        assert_eq!(report.winner, Some(1));
+        assert_eq!(report.templates.len(), 2);
+        assert_eq!(report.templates[0].spawned, 2);
+        assert_eq!(report.templates[0].lost, 1);
+        assert_eq!(report.templates[0].kills, 3);
+        assert!(report.to_csv().contains("\n1,FIGHTER,1\n"));
+
+        // a finished battle is decided by its victory condition, even with both teams left
+        let result = BattleResult {
+            winner: Some(2),
+            condition: VictoryCondition::ProtectVip { team: 1 },
+            duration: 42.0,
+        };
+        let survivors = [(1, AgentCategory::FIGHTER), (2, AgentCategory::FIGHTER)];
+        let report = recorder.report(&stats, &survivors, Some(&result));
+        assert_eq!(report.winner, Some(2));
+        assert_eq!(report.duration, 42.0);
+    }
+}
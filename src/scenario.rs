@@ -0,0 +1,347 @@
+//! Scenarios describing the fleets of a battle, the waves of reinforcements that join it and how it is won.
+//!
+//! Scenarios are `.scenario.ron` assets - see `assets/scenarios` for examples.
+
+use std::{f32::consts::TAU, fmt};
+
+use bevy::{asset::LoadState, prelude::*};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{
+    battle::{BattleClock, BattleState, BattleWaves, VictoryCondition, VictoryConditions, Vip},
+    combat::Team,
+    game::rng::GameplayRng,
+    replay::{is_playing_back, ReplayPlayer},
+    templates::{
+        loader::{RonAsset, RonAssetLoader},
+        ships::{asset::TemplateSpawner, spawn::SpawnBundle},
+    },
+};
+
+/// File extension of scenario assets.
+pub const SCENARIO_EXTENSION: &str = "scenario.ron";
+
+/// The fleets, reinforcements and victory conditions of a battle.
+#[derive(Asset, TypePath, Resource, Deserialize, Clone)]
+pub struct Scenario {
+    /// Ships present when the battle starts.
+    pub fleets: Vec<Squadron>,
+    #[serde(default)]
+    pub waves: Vec<Wave>,
+    #[serde(default)]
+    pub victory_conditions: Vec<VictoryCondition>,
+    /// The asset path the scenario was loaded from, if any.
+    #[serde(skip)]
+    pub path: Option<String>,
+}
+
+impl RonAsset for Scenario {
+    const EXTENSION: &'static str = SCENARIO_EXTENSION;
+}
+
+/// A group of ships of one kind, spawned together.
+#[derive(Deserialize, Clone)]
+pub struct Squadron {
+    pub team: i32,
+    pub ship: ShipKind,
+    pub count: u32,
+    pub area: SpawnArea,
+    #[serde(default)]
+    pub formation: Formation,
+    /// Heading of the ships, in radians. Ships without a heading face in random directions.
+    #[serde(default)]
+    pub heading: Option<f32>,
+    /// Whether the ships must be protected, see [VictoryCondition::ProtectVip].
+    #[serde(default)]
+    pub vip: bool,
+}
+
+/// The [ShipTemplate](crate::templates::ships::asset::ShipTemplate) a squadron is spawned from.
+#[derive(Deserialize, Clone)]
+pub enum ShipKind {
+    Drone,
+    SmallShip,
+    RocketFrigate,
+    /// The template at the asset path.
+    Template(String),
+}
+
+impl ShipKind {
+    /// Asset path of the ship's template.
+    pub fn template_path(&self) -> &str {
+        match self {
+            ShipKind::Drone => "ships/drone.ship.ron",
+            ShipKind::SmallShip => "ships/small_ship.ship.ron",
+            ShipKind::RocketFrigate => "ships/rocket_frigate.ship.ron",
+            ShipKind::Template(path) => path,
+        }
+    }
+}
+
+/// A rectangle that ships are spawned in.
+#[derive(Deserialize, Clone, Copy)]
+pub struct SpawnArea {
+    pub centre: (f32, f32),
+    /// Half the width and height of the area. The sign is ignored.
+    pub half_size: (f32, f32),
+}
+
+/// How the ships of a squadron are arranged in their [SpawnArea].
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Formation {
+    /// Ships are placed at random within the area.
+    #[default]
+    Scattered,
+    /// Ships are placed in a vertical line through the centre of the area, the given distance apart.
+    Line { spacing: f32 },
+    /// Ships are placed in rows of the given number of columns, centred on the area.
+    Grid { columns: u32, spacing: f32 },
+}
+
+/// Reinforcements that join the battle after a number of seconds.
+#[derive(Deserialize, Clone)]
+pub struct Wave {
+    /// Time after the battle starts that the wave arrives, in seconds.
+    pub time: f32,
+    /// If set, the wave arrives again after every interval, in seconds.
+    #[serde(default)]
+    pub repeat: Option<f32>,
+    pub squadrons: Vec<Squadron>,
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Parse(ron::error::SpannedError),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Parse(err) => write!(f, "could not parse scenario: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl Scenario {
+    pub fn parse(text: &str) -> Result<Self, ScenarioError> {
+        ron::from_str(text).map_err(ScenarioError::Parse)
+    }
+}
+
+impl Formation {
+    /// Position of the ship with the given index in a squadron of `count` ships.
+    pub fn position(
+        &self,
+        index: u32,
+        count: u32,
+        area: &SpawnArea,
+        rng: &mut GameplayRng,
+    ) -> Vec2 {
+        let centre = Vec2::new(area.centre.0, area.centre.1);
+        match *self {
+            Formation::Scattered => {
+                let (half_width, half_height) = (area.half_size.0.abs(), area.half_size.1.abs());
+                centre
+                    + Vec2::new(
+                        rng.gen_range(-half_width..=half_width),
+                        rng.gen_range(-half_height..=half_height),
+                    )
+            }
+            Formation::Line { spacing } => {
+                let offset = index as f32 - (count as f32 - 1.0) / 2.0;
+                centre + Vec2::new(0.0, offset * spacing)
+            }
+            Formation::Grid { columns, spacing } => {
+                let columns = columns.max(1);
+                let rows = count.div_ceil(columns);
+                let column = (index % columns) as f32 - (columns.min(count) as f32 - 1.0) / 2.0;
+                let row = (index / columns) as f32 - (rows as f32 - 1.0) / 2.0;
+                centre + Vec2::new(column, row) * spacing
+            }
+        }
+    }
+}
+
+/// Spawn commands for each ship of a squadron.
+fn spawn_squadron(
+    commands: &mut Commands,
+    squadron: &Squadron,
+    asset_server: &AssetServer,
+    rng: &mut GameplayRng,
+) {
+    for index in 0..squadron.count {
+        let position = squadron
+            .formation
+            .position(index, squadron.count, &squadron.area, rng);
+        let heading = squadron.heading.unwrap_or_else(|| rng.gen_range(0.0..TAU));
+        let transform = Transform {
+            translation: position.extend(0.0),
+            rotation: Quat::from_rotation_z(heading),
+            scale: Vec3::splat(0.5),
+        };
+        let mut entity = commands.spawn(SpawnBundle {
+            spawn: TemplateSpawner(asset_server.load(squadron.ship.template_path().to_string())),
+            transform,
+            team: Team(squadron.team),
+        });
+        if squadron.vip {
+            entity.insert(Vip);
+        }
+    }
+}
+
+/// Time at which each wave of the scenario next arrives, or `None` once it will not arrive again.
+#[derive(Resource)]
+struct WaveSchedule(Vec<Option<f32>>);
+
+/// The scenario to start once its asset has loaded. The battle is not started while a scenario is pending.
+#[derive(Resource)]
+pub struct PendingScenario(pub Handle<Scenario>);
+
+/// Runs a [Scenario]: spawns its fleets, sends in its waves and sets its [VictoryConditions].
+///
+/// Requires the [BattlePlugin](crate::battle::BattlePlugin) and an `AssetPlugin`. The starting fleets are not spawned
+/// when a replay is being played back, as the replay holds them.
+pub enum ScenarioPlugin {
+    /// Load the `.scenario.ron` asset at the path.
+    Load(String),
+    /// Run a scenario that has already been read or built.
+    Run(Scenario),
+}
+
+impl Plugin for ScenarioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Scenario>()
+            .init_asset_loader::<RonAssetLoader<Scenario>>();
+        let handle = match self {
+            ScenarioPlugin::Load(path) => app.world.resource::<AssetServer>().load(path.clone()),
+            ScenarioPlugin::Run(scenario) => app
+                .world
+                .resource_mut::<Assets<Scenario>>()
+                .add(scenario.clone()),
+        };
+        app.insert_resource(PendingScenario(handle));
+        app.add_systems(
+            FixedFirst,
+            start_scenario.run_if(resource_exists::<PendingScenario>),
+        );
+        app.add_systems(
+            FixedUpdate,
+            spawn_waves.run_if(in_state(BattleState::Running)),
+        );
+    }
+}
+
+/// Starts the [PendingScenario] once it has loaded, or drops it if it failed to load.
+pub fn start_scenario(
+    mut commands: Commands,
+    pending: Res<PendingScenario>,
+    scenarios: Res<Assets<Scenario>>,
+    asset_server: Res<AssetServer>,
+    player: Option<Res<ReplayPlayer>>,
+    mut rng: ResMut<GameplayRng>,
+) {
+    let Some(scenario) = scenarios.get(&pending.0) else {
+        if asset_server.load_state(&pending.0) == LoadState::Failed {
+            error!("Failed to load scenario {:?}", pending.0.path());
+            commands.remove_resource::<PendingScenario>();
+        }
+        return;
+    };
+
+    if !is_playing_back(player) {
+        for squadron in scenario.fleets.iter() {
+            spawn_squadron(&mut commands, squadron, &asset_server, &mut rng);
+        }
+    }
+    let mut scenario = scenario.clone();
+    scenario.path = pending.0.path().map(|path| path.to_string());
+    commands.insert_resource(VictoryConditions(scenario.victory_conditions.clone()));
+    commands.insert_resource(WaveSchedule(
+        scenario.waves.iter().map(|wave| Some(wave.time)).collect(),
+    ));
+    commands.insert_resource(scenario);
+    commands.remove_resource::<PendingScenario>();
+}
+
+fn spawn_waves(
+    mut commands: Commands,
+    scenario: Res<Scenario>,
+    mut schedule: ResMut<WaveSchedule>,
+    mut waves: ResMut<BattleWaves>,
+    clock: Res<BattleClock>,
+    asset_server: Res<AssetServer>,
+    mut rng: ResMut<GameplayRng>,
+) {
+    for (wave, next_time) in scenario.waves.iter().zip(schedule.0.iter_mut()) {
+        let Some(time) = *next_time else {
+            continue;
+        };
+        if clock.0 < time {
+            continue;
+        }
+
+        for squadron in wave.squadrons.iter() {
+            spawn_squadron(&mut commands, squadron, &asset_server, &mut rng);
+        }
+        waves.launched += 1;
+        *next_time = wave
+            .repeat
+            .map(|interval| time + interval.max(f32::EPSILON));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::rng::SimulationSeed;
+
+    #[test]
+    fn test_scenario_files_parse() {
+        let directory = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/scenarios");
+        for entry in std::fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+            if !path.to_string_lossy().ends_with(SCENARIO_EXTENSION) {
+                continue;
+            }
+            let text = std::fs::read_to_string(&path).unwrap();
+            if let Err(err) = Scenario::parse(&text) {
+                panic!("{:?}: {}", path, err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scattered_formation_ignores_sign_of_area() {
+        let mut rng = GameplayRng::from_seed(SimulationSeed(0));
+        let area = SpawnArea {
+            centre: (0.0, 0.0),
+            half_size: (-20.0, -10.0),
+        };
+        for index in 0..10 {
+            let position = Formation::Scattered.position(index, 10, &area, &mut rng);
+            assert!(position.x.abs() <= 20.0 && position.y.abs() <= 10.0);
+        }
+    }
+
+    #[test]
+    fn test_grid_formation_is_centred() {
+        let mut rng = GameplayRng::from_seed(SimulationSeed(0));
+        let area = SpawnArea {
+            centre: (100.0, 0.0),
+            half_size: (50.0, 50.0),
+        };
+        let grid = Formation::Grid {
+            columns: 2,
+            spacing: 10.0,
+        };
+        let positions: Vec<_> = (0..4)
+            .map(|index| grid.position(index, 4, &area, &mut rng))
+            .collect();
+        assert_eq!(positions[0], Vec2::new(95.0, -5.0));
+        assert_eq!(positions[3], Vec2::new(105.0, 5.0));
+    }
+}
@@ -0,0 +1,45 @@
+//! Spatial hashing, for quickly finding things near a position.
+
+use bevy::prelude::*;
+use multimap::MultiMap;
+
+pub const HASH_CELL_SIZE: f32 = 50.0;
+
+/// Convert a position to cell coordinates
+pub fn get_cell_coordinates(position: Vec3) -> (i32, i32) {
+    (
+        (position.x / HASH_CELL_SIZE).floor() as i32,
+        (position.y / HASH_CELL_SIZE).floor() as i32,
+    )
+}
+
+/// Items sorted into square cells by position.
+pub struct SpatialHash<T> {
+    cells: MultiMap<(i32, i32), T>,
+}
+
+impl<T> Default for SpatialHash<T> {
+    fn default() -> Self {
+        SpatialHash {
+            cells: MultiMap::new(),
+        }
+    }
+}
+
+impl<T> SpatialHash<T> {
+    pub fn insert(&mut self, position: Vec3, item: T) {
+        self.cells.insert(get_cell_coordinates(position), item);
+    }
+
+    /// Items in the cells overlapping a square of the given half-width around a position.
+    ///
+    /// Items may be further than `radius` from the position, so callers should check the distance themselves.
+    pub fn near(&self, position: Vec3, radius: f32) -> impl Iterator<Item = &T> + '_ {
+        let min_coords = get_cell_coordinates(position - Vec3::splat(radius));
+        let max_coords = get_cell_coordinates(position + Vec3::splat(radius));
+        (min_coords.0..=max_coords.0)
+            .flat_map(move |x| (min_coords.1..=max_coords.1).map(move |y| (x, y)))
+            .filter_map(move |cell| self.cells.get_vec(&cell))
+            .flatten()
+    }
+}
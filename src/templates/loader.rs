@@ -0,0 +1,72 @@
+//! Loading of template assets written in RON.
+
+use std::{fmt, marker::PhantomData};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
+use serde::de::DeserializeOwned;
+
+/// An asset that is deserialized from a RON file.
+pub trait RonAsset: Asset + DeserializeOwned {
+    /// File extension of the asset, without the preceding dot.
+    const EXTENSION: &'static str;
+}
+
+#[derive(Debug)]
+pub enum RonLoaderError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl fmt::Display for RonLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RonLoaderError::Io(err) => write!(f, "could not read asset: {}", err),
+            RonLoaderError::Parse(err) => write!(f, "could not parse asset: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for RonLoaderError {}
+
+/// Loads assets of type `A` from files with extension [RonAsset::EXTENSION].
+pub struct RonAssetLoader<A> {
+    marker: PhantomData<fn() -> A>,
+}
+
+impl<A> Default for RonAssetLoader<A> {
+    fn default() -> Self {
+        RonAssetLoader {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<A: RonAsset> AssetLoader for RonAssetLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = RonLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<A, RonLoaderError>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader
+                .read_to_end(&mut bytes)
+                .await
+                .map_err(RonLoaderError::Io)?;
+            ron::de::from_bytes(&bytes).map_err(RonLoaderError::Parse)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        std::slice::from_ref(&A::EXTENSION)
+    }
+}
@@ -3,4 +3,5 @@
 //! As a workaround, we create template scripts which will produce different entities in our game.
 
 pub mod weapons;
-pub mod ships;
\ No newline at end of file
+pub mod ships;
+pub mod loader;
\ No newline at end of file
@@ -0,0 +1,728 @@
+//! Ship templates loaded from `.ship.ron` asset files.
+//!
+//! To spawn a ship from a file, insert a [SpawnBundle](super::spawn::SpawnBundle) with a [TemplateSpawner] that holds
+//! the loaded [ShipTemplate]. Spawn commands wait until their template has finished loading.
+
+use std::mem::discriminant;
+
+use bevy::{
+    asset::LoadState,
+    ecs::{query::QueryData, system::EntityCommands},
+    prelude::*,
+};
+#[cfg(feature = "graphics")]
+use bevy::{sprite::Mesh2dHandle, utils::HashMap};
+use serde::Deserialize;
+
+use crate::{
+    ai::{
+        aggression::{
+            AgentCategory, AggroLocation, AggroRadius, HardpointTargetingBundle, RetargetBehavior,
+            TargetingOrders,
+        },
+        idle::{IdleBehavior, RoamBehavior},
+        movement::TurnToDestinationBehavior,
+    },
+    combat::{
+        armor::Armor,
+        damage::{LastDamageTimer, Resistances},
+        effectiveness::EffectivenessBuff,
+        effects::Effector,
+        evasion::Evasion,
+        events::{root_instigator, CombatEvent},
+        mortal::{Health, MaxHealth, Mortal},
+        projectile::CircularHitBox,
+        shields::{
+            DirectionalShield, MaxShieldHP, Shield, ShieldAbsorption, ShieldComponents,
+            ShieldRegeneration,
+        },
+        status::{StatusEffect, StatusKind},
+        subsystems::{Subsystem, SubsystemKind, WEAPON_SUBSYSTEM_RADIUS},
+        targets::InheritTargetFromParent,
+        tools::{Cooldown, LeadTarget, TargettedTool},
+        turrets::TurretMount,
+        Target, Team,
+    },
+    fx::{animated::AnimatedEffects, death::DeathEffect},
+    movement::{Mass, MaxTurnSpeed, MovementBundle, Thrust},
+    templates::{
+        loader::{RonAsset, RonAssetLoader},
+        weapons::asset::WeaponDefinition,
+    },
+};
+
+use super::spawn::{place_spawned_ship, SpawnCommand, TemplateName};
+#[cfg(feature = "graphics")]
+use crate::materials::ShipMaterial;
+
+/// File extension of ship template assets.
+pub const SHIP_TEMPLATE_EXTENSION: &str = "ship.ron";
+
+/// The stats and loadout of a class of ship.
+#[derive(Asset, TypePath, Deserialize, Clone)]
+pub struct ShipTemplate {
+    pub category: AgentCategory,
+    pub health: f32,
+    pub mass: f32,
+    pub thrust: f32,
+    /// Maximum turn speed, in radians per second.
+    pub max_turn_speed: f32,
+    pub evasion: f32,
+    pub hit_box_radius: f32,
+    #[serde(default)]
+    pub resistances: Resistances,
+    /// Multiplies the effectiveness of the ship's attacks, e.g. for a veteran crew. See [EffectivenessBuff].
+    #[serde(default)]
+    pub effectiveness_buff: Option<f32>,
+    #[serde(default)]
+    pub shield: Option<ShieldTemplate>,
+    #[serde(default)]
+    pub armor: Option<Armor>,
+    #[serde(default = "default_aggro_radius")]
+    pub aggro_radius: f32,
+    pub targeting: TargetingOrders,
+    #[serde(default = "default_retarget_interval")]
+    pub retarget_interval: f32,
+    #[serde(default)]
+    pub hardpoints: Vec<Hardpoint>,
+    /// Damageable parts of the ship other than its weapons.
+    #[serde(default)]
+    pub subsystems: Vec<SubsystemTemplate>,
+    pub dying_explosion: AnimatedEffects,
+    pub death_explosion: AnimatedEffects,
+    /// How the ship is drawn. Ships without a sprite are invisible.
+    #[serde(default)]
+    pub sprite: Option<ShipSprite>,
+}
+
+fn default_aggro_radius() -> f32 {
+    1000.0
+}
+
+fn default_retarget_interval() -> f32 {
+    4.0
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct ShieldTemplate {
+    pub health: f32,
+    pub radius: f32,
+    #[serde(default)]
+    pub absorption: ShieldAbsorption,
+    /// Whether the shield is split into quadrants, see [DirectionalShield].
+    #[serde(default)]
+    pub directional: bool,
+    /// How the shield recovers. Shields without regeneration only ever go down.
+    #[serde(default)]
+    pub regeneration: Option<ShieldRegeneration>,
+}
+
+impl ShieldTemplate {
+    /// Gives the ship a fully charged shield.
+    fn insert(&self, ship: &mut EntityCommands) {
+        ship.insert((
+            Shield {
+                health: self.health,
+                radius: self.radius,
+            },
+            self.absorption,
+            MaxShieldHP(self.health),
+        ));
+        if let Some(regeneration) = self.regeneration {
+            ship.insert(regeneration);
+        }
+        if self.directional {
+            ship.insert(DirectionalShield::new(self.health));
+        }
+    }
+}
+
+/// A weapon mounted on the ship.
+///
+/// The cooldown, range and cone of a [WeaponKind::Definition] are replaced by those of the definition once it loads.
+#[derive(Deserialize, Clone)]
+pub struct Hardpoint {
+    /// Position of the weapon relative to the hull.
+    pub offset: (f32, f32),
+    /// Rotation of the weapon relative to the hull, in radians.
+    #[serde(default)]
+    pub rotation: f32,
+    pub weapon: WeaponKind,
+    #[serde(default)]
+    pub cooldown: f32,
+    #[serde(default)]
+    pub range: f32,
+    /// Width of the firing cone, in radians.
+    #[serde(default)]
+    pub cone: f32,
+    /// Orders for a weapon that picks its own targets within its range and firing arc. Weapons without orders fire at
+    /// the ship's target.
+    #[serde(default)]
+    pub targeting: Option<TargetingOrders>,
+    /// Seconds between a weapon with [Hardpoint::targeting] dropping its target to look for a better one. By default
+    /// it looks every step.
+    #[serde(default)]
+    pub retarget_interval: f32,
+    /// Turret that turns the weapon toward its target, centred on the hardpoint's rotation.
+    #[serde(default)]
+    pub turret: Option<TurretMount>,
+    /// Health of the weapon as a [Subsystem]. Weapons without health cannot be knocked out.
+    #[serde(default)]
+    pub health: Option<f32>,
+}
+
+/// A part of the ship that can be knocked out, see [Subsystem].
+#[derive(Deserialize, Clone, Copy)]
+pub struct SubsystemTemplate {
+    pub kind: SubsystemKind,
+    /// Position of the subsystem relative to the hull.
+    pub offset: (f32, f32),
+    pub health: f32,
+    /// Hits landing within this distance of the subsystem damage it, in the same units as the offset.
+    pub radius: f32,
+}
+
+/// The weapons that can be fitted to a [Hardpoint].
+#[derive(Deserialize, Clone)]
+pub enum WeaponKind {
+    SmallRocketLauncher,
+    Cannon,
+    PointDefense,
+    /// The path of a [WeaponDefinition] asset.
+    Definition(String),
+}
+
+impl WeaponKind {
+    pub fn effector(&self, asset_server: &AssetServer) -> Effector {
+        match self {
+            WeaponKind::SmallRocketLauncher => Effector::new(super::rockets::small_rocket_launcher),
+            WeaponKind::Cannon => Effector::new(super::shells::cannon),
+            WeaponKind::PointDefense => {
+                Effector::new(crate::templates::weapons::point_defense_attack)
+            }
+            WeaponKind::Definition(path) => {
+                Effector::from_weapon(asset_server.load::<WeaponDefinition>(path.clone()))
+            }
+        }
+    }
+
+    /// Speed of the unguided projectiles fired by the weapon, if any.
+    pub fn projectile_speed(&self) -> Option<f32> {
+        match self {
+            WeaponKind::Cannon => Some(super::shells::CANNON_SHELL_SPEED),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ShipSprite {
+    /// Path of the base texture.
+    pub texture: String,
+    /// Path of the team color mask.
+    pub mask: String,
+    pub half_size: (f32, f32),
+}
+
+impl RonAsset for ShipTemplate {
+    const EXTENSION: &'static str = SHIP_TEMPLATE_EXTENSION;
+}
+
+/// Spawn command for a ship described by a [ShipTemplate].
+#[derive(Component, Clone)]
+pub struct TemplateSpawner(pub Handle<ShipTemplate>);
+
+/// The template a ship was spawned from. Used to apply changes when the template is reloaded.
+#[derive(Component, Clone)]
+pub struct FromShipTemplate(pub Handle<ShipTemplate>);
+
+/// Meshes shared by all ships spawned from the same template.
+#[cfg(feature = "graphics")]
+#[derive(Resource, Default)]
+pub struct ShipTemplateMeshes(HashMap<AssetId<ShipTemplate>, Mesh2dHandle>);
+
+/// A hardpoint or subsystem fitted to a ship from its [ShipTemplate]. Replaced when the template is reloaded.
+#[derive(Component)]
+pub struct ShipFitting;
+
+/// A fresh subsystem, worn down to the same fraction of health as the one it replaces.
+///
+/// A replaced subsystem that was destroyed leaves the new one with no health, so that it is knocked out again along
+/// with everything it provides.
+fn wear_subsystem(subsystem: Subsystem, previous: Option<&Subsystem>) -> Subsystem {
+    let Some(previous) = previous.filter(|previous| {
+        discriminant(&previous.kind) == discriminant(&subsystem.kind) && previous.max_health > 0.0
+    }) else {
+        return subsystem;
+    };
+    Subsystem {
+        health: if previous.destroyed {
+            previous.health.min(0.0)
+        } else {
+            previous.health / previous.max_health * subsystem.max_health
+        },
+        ..subsystem
+    }
+}
+
+/// Spawns the hardpoints and subsystems of a template, ready to be added as children of a ship.
+///
+/// Subsystems take on the wear of those in `previous` at the same position in the list of fittings.
+fn spawn_fittings(
+    commands: &mut Commands,
+    template: &ShipTemplate,
+    asset_server: &AssetServer,
+    previous: &[Option<Subsystem>],
+) -> Vec<Entity> {
+    let previous = |index: usize| previous.get(index).and_then(Option::as_ref);
+    let weapons: Vec<Entity> = template
+        .hardpoints
+        .iter()
+        .enumerate()
+        .map(|(index, hardpoint)| {
+            let mut weapon = commands.spawn((
+                TransformBundle::from_transform(Transform {
+                    translation: Vec3::new(hardpoint.offset.0, hardpoint.offset.1, 0.0),
+                    rotation: Quat::from_rotation_z(hardpoint.rotation),
+                    scale: Vec3::splat(1.0),
+                }),
+                ShipFitting,
+            ));
+            weapon.insert(Target::default());
+            match hardpoint.targeting {
+                Some(orders) => weapon.insert(HardpointTargetingBundle::new(
+                    orders,
+                    hardpoint.range,
+                    hardpoint.retarget_interval,
+                )),
+                None => weapon.insert(InheritTargetFromParent),
+            };
+            weapon.insert((
+                Cooldown::new(hardpoint.cooldown),
+                TargettedTool {
+                    range: hardpoint.range,
+                    cone: hardpoint.cone,
+                    armed: true,
+                    firing: false,
+                },
+                hardpoint.weapon.effector(asset_server),
+            ));
+            if let Some(turret) = hardpoint.turret {
+                weapon.insert(turret.turret(hardpoint.rotation));
+            }
+            if let Some(projectile_speed) = hardpoint.weapon.projectile_speed() {
+                weapon.insert(LeadTarget { projectile_speed });
+            }
+            if let Some(health) = hardpoint.health {
+                weapon.insert(wear_subsystem(
+                    Subsystem::new(SubsystemKind::Weapon, health, WEAPON_SUBSYSTEM_RADIUS),
+                    previous(index),
+                ));
+            }
+            weapon.id()
+        })
+        .collect();
+    let subsystems = template
+        .subsystems
+        .iter()
+        .enumerate()
+        .map(|(index, subsystem)| {
+            commands
+                .spawn((
+                    TransformBundle::from_transform(Transform::from_xyz(
+                        subsystem.offset.0,
+                        subsystem.offset.1,
+                        0.0,
+                    )),
+                    wear_subsystem(
+                        Subsystem::new(subsystem.kind, subsystem.health, subsystem.radius),
+                        previous(template.hardpoints.len() + index),
+                    ),
+                    ShipFitting,
+                ))
+                .id()
+        });
+    weapons.into_iter().chain(subsystems).collect()
+}
+
+fn spawn_template(
+    commands: &mut Commands,
+    template: &ShipTemplate,
+    asset_server: &AssetServer,
+) -> Entity {
+    let fittings = spawn_fittings(commands, template, asset_server, &[]);
+
+    let mut ship = commands.spawn(TransformBundle::default());
+    ship.insert(MovementBundle {
+        max_turn_speed: MaxTurnSpeed::new(template.max_turn_speed),
+        mass: Mass(template.mass),
+        thrust: Thrust(template.thrust),
+        ..default()
+    })
+    .insert(IdleBehavior)
+    .insert(TurnToDestinationBehavior::default())
+    .insert(RoamBehavior {
+        centre: Vec3::default(),
+        radius: 10.0,
+    })
+    .insert((
+        AggroRadius(template.aggro_radius),
+        AggroLocation::default(),
+        template.targeting,
+        Target::default(),
+        Health(template.health),
+        LastDamageTimer(0.0),
+        MaxHealth(template.health),
+        template.category,
+        Mortal,
+        RetargetBehavior {
+            interval: template.retarget_interval,
+            remaining_time: template.retarget_interval,
+        },
+    ))
+    .insert(DeathEffect {
+        time_to_explosion: 0.1,
+        time_to_smoke: 0.05,
+        dying_explosion: template.dying_explosion,
+        death_explosion: template.death_explosion,
+    })
+    .insert(CircularHitBox {
+        radius: template.hit_box_radius,
+    })
+    .insert(Evasion::new(template.evasion))
+    .insert(template.resistances)
+    .push_children(&fittings);
+    if let Some(shield) = template.shield {
+        shield.insert(&mut ship);
+    }
+    if let Some(armor) = template.armor {
+        ship.insert(armor);
+    }
+    if let Some(buff) = template.effectiveness_buff {
+        ship.insert(EffectivenessBuff(buff));
+    }
+    ship.id()
+}
+
+/// Names a template after its file, e.g. `drone` for `ships/drone.ship.ron`.
+fn template_name(handle: &Handle<ShipTemplate>) -> String {
+    let Some(file_name) = handle.path().and_then(|path| path.path().file_name()) else {
+        return String::new();
+    };
+    let file_name = file_name.to_string_lossy();
+    file_name.split('.').next().unwrap_or_default().to_string()
+}
+
+/// Spawns ships for each [TemplateSpawner] whose template has loaded.
+///
+/// Spawn commands are handled as in [spawn_ships_and_despawn_spawn_commands](super::spawn::spawn_ships_and_despawn_spawn_commands).
+/// Spawn commands whose template failed to load are dropped.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_ships_from_templates(
+    mut commands: Commands,
+    mut events: EventWriter<CombatEvent>,
+    templates: Res<Assets<ShipTemplate>>,
+    query: Query<SpawnCommand<TemplateSpawner>>,
+    team_query: Query<&Team>,
+    parent_query: Query<&Parent>,
+    asset_server: Res<AssetServer>,
+    #[cfg(feature = "graphics")] mut template_meshes: ResMut<ShipTemplateMeshes>,
+    #[cfg(feature = "graphics")] mut meshes: Option<ResMut<Assets<Mesh>>>,
+    #[cfg(feature = "graphics")] mut materials: Option<ResMut<Assets<ShipMaterial>>>,
+) {
+    for (spawner_entity, spawner, transform, team_option, instigators, vip) in query.iter() {
+        let Some(template) = templates.get(&spawner.0) else {
+            if asset_server.load_state(&spawner.0) == LoadState::Failed {
+                error!("Failed to load ship template {:?}", spawner.0.path());
+                commands.entity(spawner_entity).despawn();
+            }
+            continue;
+        };
+
+        let root = root_instigator(instigators, &parent_query);
+        let created = spawn_template(&mut commands, template, &asset_server);
+        commands.entity(created).insert((
+            FromShipTemplate(spawner.0.clone()),
+            TemplateName(template_name(&spawner.0)),
+        ));
+
+        #[cfg(feature = "graphics")]
+        if let (Some(sprite), Some(meshes), Some(materials)) =
+            (&template.sprite, &mut meshes, &mut materials)
+        {
+            let mesh = template_meshes
+                .0
+                .entry(spawner.0.id())
+                .or_insert_with(|| {
+                    meshes
+                        .add(Mesh::from(Rectangle {
+                            half_size: Vec2::new(sprite.half_size.0, sprite.half_size.1),
+                        }))
+                        .into()
+                })
+                .clone();
+            commands.entity(created).insert((
+                mesh,
+                materials.add(ShipMaterial {
+                    color: Color::rgba(0.0, 0.0, 1.0, 1.0),
+                    last_damaged_time: 1.0,
+                    base_texture: asset_server.load(&sprite.texture),
+                    color_mask: asset_server.load(&sprite.mask),
+                }),
+                VisibilityBundle::default(),
+            ));
+        }
+
+        place_spawned_ship(
+            &mut commands,
+            created,
+            transform,
+            team_option,
+            instigators.0,
+            root,
+            vip,
+            &team_query,
+        );
+        events.send(CombatEvent::Spawned {
+            instigator: root,
+            entity: created,
+        });
+        commands.entity(spawner_entity).despawn();
+    }
+}
+
+/// Stats of a ship that are updated when its template is reloaded.
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct TemplatedStats {
+    entity: Entity,
+    children: Option<&'static Children>,
+    from_template: &'static FromShipTemplate,
+    health: &'static mut Health,
+    max_health: &'static mut MaxHealth,
+    mass: &'static mut Mass,
+    thrust: &'static mut Thrust,
+    max_turn_speed: &'static mut MaxTurnSpeed,
+    evasion: &'static mut Evasion,
+    hit_box: &'static mut CircularHitBox,
+    aggro_radius: &'static mut AggroRadius,
+    orders: &'static mut TargetingOrders,
+    category: &'static mut AgentCategory,
+    resistances: &'static mut Resistances,
+    shield: Option<&'static mut Shield>,
+    directional: Has<DirectionalShield>,
+    armor: Option<&'static mut Armor>,
+}
+
+/// Applies changes to reloaded templates to the ships spawned from them.
+///
+/// Stats are updated in place, keeping each ship's fraction of health. Stats changed by a status are set from the
+/// template and the status applied again. Shields and armor are added or removed to match the template. Hardpoints
+/// and subsystems are replaced with fresh ones, worn down as much as those they replace.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_reloaded_templates(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<ShipTemplate>>,
+    templates: Res<Assets<ShipTemplate>>,
+    asset_server: Res<AssetServer>,
+    #[cfg(feature = "graphics")] mut template_meshes: ResMut<ShipTemplateMeshes>,
+    mut query: Query<TemplatedStats>,
+    mut status_query: Query<&mut StatusEffect>,
+    fitting_query: Query<Option<&Subsystem>, With<ShipFitting>>,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        let Some(template) = templates.get(*id) else {
+            continue;
+        };
+        #[cfg(feature = "graphics")]
+        template_meshes.0.remove(id);
+
+        for mut ship in query.iter_mut() {
+            if ship.from_template.0.id() != *id {
+                continue;
+            }
+            ship.health.0 = if ship.max_health.0 > 0.0 {
+                ship.health.0 / ship.max_health.0 * template.health
+            } else {
+                template.health
+            };
+            ship.max_health.0 = template.health;
+            ship.mass.0 = template.mass;
+            ship.hit_box.radius = template.hit_box_radius;
+            ship.aggro_radius.0 = template.aggro_radius;
+            *ship.orders = template.targeting;
+            *ship.category = template.category;
+            *ship.resistances = template.resistances;
+
+            // Thrust lost to destroyed engines is taken away again once their replacements are knocked out.
+            let mut thrust = template.thrust;
+            let mut max_turn_speed = template.max_turn_speed;
+            let mut evasion = template.evasion;
+            for mut status in status_query.iter_mut() {
+                if status.target != ship.entity {
+                    continue;
+                }
+                match status.kind {
+                    StatusKind::Emp => thrust = status.rebase(thrust),
+                    StatusKind::Burning(_) => {}
+                    StatusKind::Slowed(_) => max_turn_speed = status.rebase(max_turn_speed),
+                    StatusKind::Marked(_) => evasion = status.rebase(evasion),
+                }
+            }
+            ship.thrust.0 = thrust;
+            ship.max_turn_speed.radians_per_second = max_turn_speed;
+            ship.evasion.base = evasion;
+
+            let mut entity = commands.entity(ship.entity);
+            match (template.shield, ship.shield.as_mut()) {
+                (Some(shield_template), Some(shield)) => {
+                    shield.radius = shield_template.radius;
+                    entity.insert((
+                        shield_template.absorption,
+                        MaxShieldHP(shield_template.health),
+                    ));
+                    match shield_template.regeneration {
+                        Some(regeneration) => entity.insert(regeneration),
+                        None => entity.remove::<ShieldRegeneration>(),
+                    };
+                    if !shield_template.directional {
+                        entity.remove::<DirectionalShield>();
+                    } else if !ship.directional {
+                        entity.insert(DirectionalShield::new(shield_template.health));
+                    }
+                }
+                (Some(shield_template), None) => shield_template.insert(&mut entity),
+                (None, _) => {
+                    entity.remove::<ShieldComponents>();
+                }
+            }
+            match (template.armor, ship.armor.as_mut()) {
+                (Some(armor_template), Some(armor)) => armor.reduction = armor_template.reduction,
+                (Some(armor_template), None) => {
+                    entity.insert(armor_template);
+                }
+                (None, _) => {
+                    entity.remove::<Armor>();
+                }
+            }
+            match template.effectiveness_buff {
+                Some(buff) => entity.insert(EffectivenessBuff(buff)),
+                None => entity.remove::<EffectivenessBuff>(),
+            };
+
+            let mut previous = Vec::new();
+            for &child in ship.children.into_iter().flatten() {
+                if let Ok(subsystem) = fitting_query.get(child) {
+                    previous.push(subsystem.copied());
+                    commands.entity(child).despawn_recursive();
+                }
+            }
+            let fittings = spawn_fittings(&mut commands, template, &asset_server, &previous);
+            commands.entity(ship.entity).push_children(&fittings);
+        }
+    }
+}
+
+pub struct ShipTemplatePlugin;
+
+impl Plugin for ShipTemplatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<ShipTemplate>()
+            .init_asset_loader::<RonAssetLoader<ShipTemplate>>();
+        #[cfg(feature = "graphics")]
+        app.init_resource::<ShipTemplateMeshes>();
+        app.add_systems(
+            FixedUpdate,
+            (apply_reloaded_templates, spawn_ships_from_templates).chain(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        combat::lifetime::Lifetime, headless::HeadlessCombatPlugins,
+        templates::ships::spawn::SpawnBundle,
+    };
+
+    #[test]
+    fn test_reload_keeps_statuses_and_matches_shields_and_armor() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, HeadlessCombatPlugins));
+        app.finish();
+        app.cleanup();
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("assets/ships/small_ship.ship.ron");
+        let mut template: ShipTemplate =
+            ron::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let handle = app
+            .world
+            .resource_mut::<Assets<ShipTemplate>>()
+            .add(template.clone());
+        app.world.spawn(SpawnBundle {
+            spawn: TemplateSpawner(handle.clone()),
+            transform: Transform::default(),
+            team: Team(1),
+        });
+        let mut ships = app.world.query_filtered::<Entity, With<FromShipTemplate>>();
+        let mut steps = 0;
+        let ship = loop {
+            app.update();
+            if let Some(ship) = ships.iter(&app.world).next() {
+                break ship;
+            }
+            steps += 1;
+            assert!(steps < 1000, "the ship was not spawned");
+        };
+        app.world.spawn((
+            StatusEffect::new(ship, StatusKind::Emp, None),
+            Lifetime {
+                seconds_remaining: 100.0,
+            },
+        ));
+        for _ in 0..10 {
+            app.update();
+        }
+        assert_eq!(app.world.get::<Thrust>(ship).unwrap().0, 0.0);
+
+        template.thrust = 300.0;
+        template.shield = None;
+        template.armor = Some(Armor {
+            reduction: 4.0,
+            health: 50.0,
+        });
+        *app.world
+            .resource_mut::<Assets<ShipTemplate>>()
+            .get_mut(&handle)
+            .unwrap() = template;
+        for _ in 0..10 {
+            app.update();
+        }
+
+        // the EMP still holds the ship in place
+        assert_eq!(app.world.get::<Thrust>(ship).unwrap().0, 0.0);
+        assert!(app.world.get::<Shield>(ship).is_none());
+        assert!(app.world.get::<MaxShieldHP>(ship).is_none());
+        assert!(app.world.get::<ShieldRegeneration>(ship).is_none());
+        assert_eq!(app.world.get::<Armor>(ship).unwrap().reduction, 4.0);
+    }
+
+    #[test]
+    fn test_ship_template_files_parse() {
+        let directory = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/ships");
+        for entry in std::fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+            if !path.to_string_lossy().ends_with(SHIP_TEMPLATE_EXTENSION) {
+                continue;
+            }
+            let text = std::fs::read_to_string(&path).unwrap();
+            if let Err(err) = ron::from_str::<ShipTemplate>(&text) {
+                panic!("{:?}: {}", path, err);
+            }
+        }
+    }
+}
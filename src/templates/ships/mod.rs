@@ -1,6 +1,6 @@
 //! Templates of different types of ship.
 
-pub mod fighters;
 pub mod spawn;
-pub mod frigates;
-pub mod rockets;
\ No newline at end of file
+pub mod rockets;
+pub mod shells;
+pub mod asset;
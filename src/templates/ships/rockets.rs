@@ -1,9 +1,8 @@
 //! Rockets and their launchers
 
-use bevy::{
-    prelude::*,
-    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
-};
+use bevy::prelude::*;
+#[cfg(feature = "graphics")]
+use bevy::sprite::Mesh2dHandle;
 
 use crate::{
     ai::{
@@ -13,16 +12,18 @@ use crate::{
         movement::TurnToDestinationBehavior,
     },
     combat::{
-        damage::LastDamageTimer, effects::Effector, evasion::Evasion, lifetime::Lifetime, mortal::{Health, MaxHealth, Mortal}, projectile::{Homing, Projectile}, CombatSystems
+        damage::LastDamageTimer, effects::Effector, evasion::Evasion, lifetime::Lifetime, mortal::{Health, MaxHealth, Mortal}, projectile::{Homing, Projectile, ProportionalNavigation}, CombatSystems
     },
     fx::{animated::AnimatedEffects, death::DeathEffect},
-    materials::ShipMaterial,
     movement::{Mass, MaxTurnSpeed, MovementBundle, Thrust},
 };
 
 use super::spawn::{spawn_ships_and_despawn_spawn_commands, SpawnShipTemplate};
+#[cfg(feature = "graphics")]
+use crate::materials::ShipMaterial;
 
 
+#[cfg(feature = "graphics")]
 #[derive(Resource)]
 pub struct RocketResources {
     rocket_color: Handle<Image>,
@@ -33,27 +34,13 @@ pub struct RocketResources {
 #[derive(Component)]
 pub struct RocketSpawner;
 impl SpawnShipTemplate for RocketSpawner {
+    #[cfg(feature = "graphics")]
     type Resources<'a> = RocketResources;
+    const NAME: &'static str = "rocket";
 
-    fn spawn<'a>(
-        &self,
-        commands: &mut Commands,
-        resources: &Res<RocketResources>,
-        materials: &mut ResMut<Assets<ShipMaterial>>,
-    ) -> Entity {
+    fn spawn(&self, commands: &mut Commands) -> Entity {
         commands
-            .spawn({
-                MaterialMesh2dBundle {
-                    mesh: resources.rocket_mesh.clone(),
-                    material: materials.add(ShipMaterial {
-                        color: Color::rgba(0.0, 0.0, 1.0, 1.0),
-                        last_damaged_time: 1.0,
-                        base_texture: resources.rocket_color.clone(),
-                        color_mask: resources.rocket_mask.clone(),
-                    }),
-                    ..default()
-                }
-            })
+            .spawn(TransformBundle::default())
             .insert(MovementBundle {
                 max_turn_speed: MaxTurnSpeed::new(10.0),
                 mass: Mass(0.2),
@@ -69,6 +56,7 @@ impl SpawnShipTemplate for RocketSpawner {
                 Mortal,
                 Projectile::new(),
                 Homing,
+                ProportionalNavigation::new(3.0),
                 Lifetime { seconds_remaining: 8.0 },
                 Effector::new(crate::templates::weapons::small_rocket_attack)
             ))
@@ -81,14 +69,35 @@ impl SpawnShipTemplate for RocketSpawner {
             .insert(Evasion::new(2.0))
             .id()
     }
+
+    #[cfg(feature = "graphics")]
+    fn add_visuals(
+        &self,
+        entity: Entity,
+        commands: &mut Commands,
+        resources: &RocketResources,
+        materials: &mut Assets<ShipMaterial>,
+    ) {
+        commands.entity(entity).insert((
+            resources.rocket_mesh.clone(),
+            materials.add(ShipMaterial {
+                color: Color::rgba(0.0, 0.0, 1.0, 1.0),
+                last_damaged_time: 1.0,
+                base_texture: resources.rocket_color.clone(),
+                color_mask: resources.rocket_mask.clone(),
+            }),
+            VisibilityBundle::default(),
+        ));
+    }
 }
 
 pub struct RocketTemplatePlugin;
+#[cfg(feature = "graphics")]
 impl RocketTemplatePlugin {
     fn setup(mut commands: Commands, assets: Res<AssetServer>, mut meshes: ResMut<Assets<Mesh>>) {
         let resources = RocketResources {
-            rocket_color: assets.load("art/rocket.png"),
-            rocket_mask: assets.load("art/rocket_mask.png"),
+            rocket_color: assets.load("art/Rocket.png"),
+            rocket_mask: assets.load("art/Rocket_mask.png"),
             rocket_mesh: meshes
             .add(Mesh::from(Rectangle {
                 half_size: Vec2::new(4.0, 8.0),
@@ -100,7 +109,11 @@ impl RocketTemplatePlugin {
 }
 impl Plugin for RocketTemplatePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, RocketTemplatePlugin::setup);
+        #[cfg(feature = "graphics")]
+        app.add_systems(
+            Startup,
+            RocketTemplatePlugin::setup.run_if(resource_exists::<Assets<Mesh>>),
+        );
         app.add_systems(
             FixedUpdate,
             (
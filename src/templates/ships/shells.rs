@@ -0,0 +1,110 @@
+//! Unguided shells and the cannons that fire them
+
+use bevy::prelude::*;
+#[cfg(feature = "graphics")]
+use bevy::sprite::Mesh2dHandle;
+
+use crate::{
+    combat::{
+        effects::Effector,
+        lifetime::Lifetime,
+        projectile::{Ballistic, Projectile},
+        CombatSystems,
+    },
+    movement::Velocity,
+};
+
+use super::spawn::{spawn_ships_and_despawn_spawn_commands, SpawnShipTemplate};
+#[cfg(feature = "graphics")]
+use crate::materials::ShipMaterial;
+
+/// Speed cannon shells are fired at.
+pub const CANNON_SHELL_SPEED: f32 = 400.0;
+
+#[cfg(feature = "graphics")]
+#[derive(Resource)]
+pub struct ShellResources {
+    shell_color: Handle<Image>,
+    shell_mask: Handle<Image>,
+    shell_mesh: Mesh2dHandle,
+}
+
+#[derive(Component)]
+pub struct CannonShellSpawner;
+impl SpawnShipTemplate for CannonShellSpawner {
+    #[cfg(feature = "graphics")]
+    type Resources<'a> = ShellResources;
+    const NAME: &'static str = "cannon_shell";
+
+    fn spawn(&self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn(TransformBundle::default())
+            .insert((
+                Velocity::default(),
+                Projectile::new(),
+                Ballistic {
+                    speed: CANNON_SHELL_SPEED,
+                    radius: 2.0,
+                },
+                Lifetime {
+                    seconds_remaining: 1.5,
+                },
+                Effector::new(crate::templates::weapons::cannon_shell_attack),
+            ))
+            .id()
+    }
+
+    #[cfg(feature = "graphics")]
+    fn add_visuals(
+        &self,
+        entity: Entity,
+        commands: &mut Commands,
+        resources: &ShellResources,
+        materials: &mut Assets<ShipMaterial>,
+    ) {
+        commands.entity(entity).insert((
+            resources.shell_mesh.clone(),
+            materials.add(ShipMaterial {
+                color: Color::rgba(0.0, 0.0, 1.0, 1.0),
+                last_damaged_time: 1.0,
+                base_texture: resources.shell_color.clone(),
+                color_mask: resources.shell_mask.clone(),
+            }),
+            VisibilityBundle::default(),
+        ));
+    }
+}
+
+pub struct ShellTemplatePlugin;
+#[cfg(feature = "graphics")]
+impl ShellTemplatePlugin {
+    fn setup(mut commands: Commands, assets: Res<AssetServer>, mut meshes: ResMut<Assets<Mesh>>) {
+        let resources = ShellResources {
+            shell_color: assets.load("art/Rocket.png"),
+            shell_mask: assets.load("art/Rocket_mask.png"),
+            shell_mesh: meshes
+                .add(Mesh::from(Rectangle {
+                    half_size: Vec2::new(1.5, 3.0),
+                }))
+                .into(),
+        };
+        commands.insert_resource(resources);
+    }
+}
+impl Plugin for ShellTemplatePlugin {
+    fn build(&self, app: &mut App) {
+        #[cfg(feature = "graphics")]
+        app.add_systems(
+            Startup,
+            ShellTemplatePlugin::setup.run_if(resource_exists::<Assets<Mesh>>),
+        );
+        app.add_systems(
+            FixedUpdate,
+            spawn_ships_and_despawn_spawn_commands::<CannonShellSpawner>.after(CombatSystems),
+        );
+    }
+}
+
+pub fn cannon(commands: &mut Commands) -> Entity {
+    commands.spawn(CannonShellSpawner).id()
+}
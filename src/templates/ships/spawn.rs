@@ -5,70 +5,151 @@
 use bevy::prelude::*;
 
 use crate::{
-    combat::{effects::Instigator, Team},
-    materials::ShipMaterial,
+    battle::Vip,
+    combat::{
+        effects::Instigator,
+        events::{root_instigator, CombatEvent, Instigators, RootInstigator},
+        stats::CombatStats,
+        Team,
+    },
 };
 
+#[cfg(feature = "graphics")]
+use crate::materials::ShipMaterial;
+
+/// A command to spawn a ship, where `T` is a [SpawnShipTemplate] or a [TemplateSpawner](super::asset::TemplateSpawner).
 #[derive(Bundle)]
 pub struct SpawnBundle<T>
 where
-    T: SpawnShipTemplate + Send + Sync + Component,
+    T: Send + Sync + Component,
 {
     pub spawn: T,
     pub transform: Transform,
     pub team: Team,
 }
 
+/// The name of the template a ship was spawned from.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct TemplateName(pub String);
+
 /// Spawns new entities according to a template.
 pub trait SpawnShipTemplate {
-    /// Resources used to spawn the entity.
+    /// Resources used to draw the entity.
+    #[cfg(feature = "graphics")]
     type Resources<'a>: Resource;
 
-    /// Spawns a new entity.
-    fn spawn(
+    /// Name identifying the template, e.g. in replays and battle reports.
+    const NAME: &'static str;
+
+    /// Spawns a new entity with the components required to simulate it.
+    fn spawn(&self, commands: &mut Commands) -> Entity;
+
+    /// Adds the mesh and material used to draw a spawned entity.
+    #[cfg(feature = "graphics")]
+    fn add_visuals(
         &self,
+        entity: Entity,
         commands: &mut Commands,
-        input: &Res<Self::Resources<'_>>,
-        materials: &mut ResMut<Assets<ShipMaterial>>,
-    ) -> Entity;
+        input: &Self::Resources<'_>,
+        materials: &mut Assets<ShipMaterial>,
+    );
 }
 
+/// Components of a spawn command with template `T`.
+pub type SpawnCommand<T> = (
+    Entity,
+    &'static T,
+    &'static Transform,
+    Option<&'static Team>,
+    Instigators<'static>,
+    Has<Vip>,
+);
+
 /// Spawns entities for each entity with template `T`.
 ///
 /// - The entity will be spawned at the given Transform.
 /// - If the spawn command entity has a `Team` component, this will be copied to the new entity.
-/// - If the spawn command has an `Instigator` component, this will be copied to the new entity.
-/// - If the spawn command ha an `Instigator` but no `Team`, it will attempt to copy the instigator's team to the created entity.
+/// - If the spawn command has an `Instigator` component, this will be copied to the new entity, along with the
+///   [RootInstigator] of the ship responsible, e.g. the ship carrying the launcher that fired a rocket.
+/// - If the spawn command has an `Instigator` but no `Team`, it will attempt to copy the team of that ship to the
+///   created entity.
+/// - If the spawn command is a [Vip], the new entity will be too.
+/// - Visuals are only added when the template resources and [ShipMaterial] assets exist, so templates can also run headless.
+/// - A [CombatEvent::Spawned] is sent for each new entity.
 pub fn spawn_ships_and_despawn_spawn_commands<T>(
     mut commands: Commands,
-    resources: Res<T::Resources<'_>>,
-    query: Query<(Entity, &T, &Transform, Option<&Team>, Option<&Instigator>)>,
+    mut events: EventWriter<CombatEvent>,
+    #[cfg(feature = "graphics")] resources: Option<Res<T::Resources<'_>>>,
+    query: Query<SpawnCommand<T>>,
     team_query: Query<&Team>,
-    mut materials: ResMut<Assets<ShipMaterial>>,
+    parent_query: Query<&Parent>,
+    #[cfg(feature = "graphics")] mut materials: Option<ResMut<Assets<ShipMaterial>>>,
 ) where
     T: Component + Send + Sync + SpawnShipTemplate,
 {
-    for (spawner_entity, spawn, transform, team_option, instigator_option) in query.iter() {
-        let transform = Transform {
-            translation: transform.translation,
-            rotation: transform.rotation,
-            scale: Vec3::splat(0.5),
-            ..default()
-        };
-        let created = spawn.spawn(&mut commands, &resources, &mut materials);
-        let mut entity_builder = commands.entity(created);
-        entity_builder
-            .insert(transform)
-            .insert(Into::<GlobalTransform>::into(transform));
-        if let Some(team) = team_option {
-            entity_builder.insert(*team);
-        }
-        if let Some(instigator) = instigator_option {
-            entity_builder.insert(*instigator);
-            if let (Ok(alt_team), None) = (team_query.get(instigator.0), team_option) {
-                entity_builder.insert(*alt_team);
-            }
+    for (spawner_entity, spawn, transform, team_option, instigators, vip) in query.iter() {
+        let root = root_instigator(instigators, &parent_query);
+        let created = spawn.spawn(&mut commands);
+        commands
+            .entity(created)
+            .insert(TemplateName(T::NAME.to_string()));
+        #[cfg(feature = "graphics")]
+        if let (Some(resources), Some(materials)) = (&resources, &mut materials) {
+            spawn.add_visuals(created, &mut commands, resources, materials);
         }
+        place_spawned_ship(
+            &mut commands,
+            created,
+            transform,
+            team_option,
+            instigators.0,
+            root,
+            vip,
+            &team_query,
+        );
+        events.send(CombatEvent::Spawned {
+            instigator: root,
+            entity: created,
+        });
         commands.entity(spawner_entity).despawn();
     }
 }
+
+/// Moves a newly spawned ship to the spawn command's location, and assigns its `Team`, `Instigator` and [CombatStats].
+#[allow(clippy::too_many_arguments)]
+pub fn place_spawned_ship(
+    commands: &mut Commands,
+    created: Entity,
+    transform: &Transform,
+    team_option: Option<&Team>,
+    instigator_option: Option<&Instigator>,
+    root: Option<Entity>,
+    vip: bool,
+    team_query: &Query<&Team>,
+) {
+    let transform = Transform {
+        translation: transform.translation,
+        rotation: transform.rotation,
+        scale: Vec3::splat(0.5),
+    };
+    let mut entity_builder = commands.entity(created);
+    entity_builder
+        .insert(transform)
+        .insert(Into::<GlobalTransform>::into(transform))
+        .insert(CombatStats::default());
+    if let Some(team) = team_option {
+        entity_builder.insert(*team);
+    }
+    if vip {
+        entity_builder.insert(Vip);
+    }
+    if let Some(instigator) = instigator_option {
+        entity_builder.insert(*instigator);
+    }
+    if let Some(root) = root {
+        entity_builder.insert(RootInstigator(root));
+        if let (Ok(alt_team), None) = (team_query.get(root), team_option) {
+            entity_builder.insert(*alt_team);
+        }
+    }
+}
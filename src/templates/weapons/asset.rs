@@ -0,0 +1,253 @@
+//! Weapons loaded from `.weapon.ron` asset files.
+//!
+//! A tool uses a definition by holding an [Effector::from_weapon]. The tool's [Cooldown], [TargettedTool] range and
+//! cone, and [SustainedBeam] are kept in step with the definition, so loadouts can be changed while the battle runs.
+
+use bevy::{prelude::*, utils::HashSet};
+use serde::Deserialize;
+
+use crate::{
+    combat::{
+        armor::ArmorPenetration,
+        attack::Attack,
+        beams::SustainedBeam,
+        blast::BlastRadius,
+        damage::{Damage, DamageType},
+        effectiveness::{CategoryBonus, CriticalHit, RangeFalloff},
+        effects::{EffectSource, Effector},
+        repair::Repair,
+        status::InflictStatus,
+        tools::{Cooldown, TargettedTool},
+    },
+    fx::{beams::BeamStyle, HitEffect},
+    templates::loader::{RonAsset, RonAssetLoader},
+};
+
+/// File extension of weapon definition assets.
+pub const WEAPON_DEFINITION_EXTENSION: &str = "weapon.ron";
+
+/// The attack and firing characteristics of a weapon.
+#[derive(Asset, TypePath, Deserialize, Clone)]
+pub struct WeaponDefinition {
+    #[serde(default)]
+    pub accuracy: f32,
+    /// Weapons without damage make no attack, e.g. repair beams.
+    #[serde(default)]
+    pub damage: f32,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    /// Reduction in the flat damage reduction of the target's armor.
+    #[serde(default)]
+    pub armor_penetration: f32,
+    /// Attacks with a blast radius damage everything nearby, see [BlastRadius].
+    #[serde(default)]
+    pub blast_radius: Option<BlastRadius>,
+    /// Status inflicted on the target by each hit.
+    #[serde(default)]
+    pub status: Option<InflictStatus>,
+    #[serde(default)]
+    pub critical: Option<CriticalHit>,
+    #[serde(default)]
+    pub range_falloff: Option<RangeFalloff>,
+    #[serde(default)]
+    pub category_bonus: Option<CategoryBonus>,
+    /// Repairs to the target, for weapons fired at allies.
+    #[serde(default)]
+    pub repair: Option<Repair>,
+    /// Weapons with a sustained beam hold it on the target, applying their damage or repairs on every tick.
+    #[serde(default)]
+    pub sustained: Option<SustainedBeam>,
+    /// How the attack is drawn. Weapons without a beam are not drawn, e.g. rocket payloads.
+    #[serde(default)]
+    pub beam: Option<BeamStyle>,
+    #[serde(default)]
+    pub hit_effect: Option<HitEffect>,
+    /// Time between shots, in seconds.
+    pub cooldown: f32,
+    pub range: f32,
+    /// Width of the firing cone, in radians.
+    pub cone: f32,
+}
+
+impl RonAsset for WeaponDefinition {
+    const EXTENSION: &'static str = WEAPON_DEFINITION_EXTENSION;
+}
+
+impl WeaponDefinition {
+    /// Spawns the attack made each time the weapon fires.
+    pub fn spawn_attack(&self, commands: &mut Commands) -> Entity {
+        let mut attack = commands.spawn_empty();
+        if self.damage > 0.0 {
+            attack.insert((
+                Attack::new(self.accuracy),
+                Damage::new(self.damage),
+                self.damage_type,
+            ));
+        }
+        if self.armor_penetration > 0.0 {
+            attack.insert(ArmorPenetration(self.armor_penetration));
+        }
+        if let Some(blast_radius) = self.blast_radius {
+            attack.insert(blast_radius);
+        }
+        if let Some(status) = self.status {
+            attack.insert(status);
+        }
+        if let Some(critical) = self.critical {
+            attack.insert(critical);
+        }
+        if let Some(range_falloff) = self.range_falloff {
+            attack.insert(range_falloff);
+        }
+        if let Some(category_bonus) = self.category_bonus {
+            attack.insert(category_bonus);
+        }
+        if let Some(repair) = self.repair {
+            attack.insert(repair);
+        }
+        if let Some(beam) = self.beam {
+            attack.insert(beam);
+        }
+        if let Some(hit_effect) = self.hit_effect {
+            attack.insert(hit_effect);
+        }
+        attack.id()
+    }
+}
+
+type WeaponToolQuery<'a> = (
+    Entity,
+    Ref<'a, Effector>,
+    &'a mut TargettedTool,
+    &'a mut Cooldown,
+    Option<&'a mut SustainedBeam>,
+);
+
+/// Copies weapon definition stats to the tools using them, when a tool is created or its definition (re)loads.
+pub fn apply_weapon_definitions(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<WeaponDefinition>>,
+    weapons: Res<Assets<WeaponDefinition>>,
+    mut tools: Query<WeaponToolQuery>,
+) {
+    let changed: HashSet<AssetId<WeaponDefinition>> = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (entity, effector, mut tool, mut cooldown, beam) in tools.iter_mut() {
+        let EffectSource::Weapon(handle) = &effector.source else {
+            continue;
+        };
+        if !effector.is_added() && !changed.contains(&handle.id()) {
+            continue;
+        }
+        let Some(weapon) = weapons.get(handle) else {
+            continue;
+        };
+        tool.range = weapon.range;
+        tool.cone = weapon.cone;
+        cooldown.duration = weapon.cooldown;
+        cooldown.remaining = cooldown.remaining.min(weapon.cooldown);
+        match (weapon.sustained, beam) {
+            (Some(sustained), Some(mut beam)) => {
+                beam.duration = sustained.duration;
+                beam.tick_interval = sustained.tick_interval;
+            }
+            (Some(sustained), None) => {
+                commands.entity(entity).insert(sustained);
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<SustainedBeam>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+pub struct WeaponTemplatePlugin;
+
+impl Plugin for WeaponTemplatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<WeaponDefinition>()
+            .init_asset_loader::<RonAssetLoader<WeaponDefinition>>();
+        app.add_systems(FixedUpdate, apply_weapon_definitions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::CommandQueue;
+
+    #[test]
+    fn test_weapon_definition_files_parse() {
+        let directory = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/weapons");
+        for entry in std::fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+            if !path
+                .to_string_lossy()
+                .ends_with(WEAPON_DEFINITION_EXTENSION)
+            {
+                continue;
+            }
+            let text = std::fs::read_to_string(&path).unwrap();
+            if let Err(err) = ron::from_str::<WeaponDefinition>(&text) {
+                panic!("{:?}: {}", path, err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sustained_beam_follows_definition() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<WeaponDefinition>()
+            .add_systems(Update, apply_weapon_definitions);
+        let definition: WeaponDefinition = ron::from_str(include_str!(
+            "../../../assets/weapons/repair_beam.weapon.ron"
+        ))
+        .unwrap();
+        let handle = app
+            .world
+            .resource_mut::<Assets<WeaponDefinition>>()
+            .add(definition);
+        let tool = app
+            .world
+            .spawn((
+                Effector::from_weapon(handle.clone()),
+                TargettedTool {
+                    range: 0.0,
+                    cone: 0.0,
+                    armed: true,
+                    firing: false,
+                },
+                Cooldown::new(0.0),
+            ))
+            .id();
+
+        app.update();
+        let beam = *app.world.get::<SustainedBeam>(tool).unwrap();
+        assert_eq!((beam.duration, beam.tick_interval), (2.0, 0.25));
+        // repairs are not attacks, so cannot miss or damage the ally
+        let repair = app
+            .world
+            .resource::<Assets<WeaponDefinition>>()
+            .get(&handle)
+            .unwrap()
+            .clone();
+        let mut queue = CommandQueue::default();
+        let effect = repair.spawn_attack(&mut Commands::new(&mut queue, &app.world));
+        queue.apply(&mut app.world);
+        assert!(app.world.get::<Repair>(effect).is_some());
+        assert!(app.world.get::<Attack>(effect).is_none());
+
+        let mut weapons = app.world.resource_mut::<Assets<WeaponDefinition>>();
+        weapons.get_mut(&handle).unwrap().sustained = None;
+        app.update();
+        assert!(app.world.get::<SustainedBeam>(tool).is_none());
+    }
+}
@@ -1,38 +1,51 @@
 //! Weapons that can deal damage.
 //!
 
+pub mod asset;
+
 use bevy::prelude::*;
 
 use crate::{
-    combat::{attack::Attack, damage::Damage},
+    ai::aggression::AgentCategory,
+    combat::{
+        armor::ArmorPenetration,
+        attack::Attack,
+        blast::BlastRadius,
+        damage::{Damage, DamageType},
+        effectiveness::CategoryBonus,
+        evasion::Unavoidable,
+    },
     fx::{beams::BeamStyle, HitEffect},
 };
 
-/// The attack from a small pulsed laser.
-pub fn pulse_laser_attack(commands: &mut Commands) -> Entity {
+pub fn small_rocket_attack(commands: &mut Commands) -> Entity {
     commands
         .spawn((
-            Attack::new(3.0),
-            Damage::new(20.0),
-            BeamStyle {
-                effect: crate::fx::animated::AnimatedEffects::BlueLaserBeam,
-                width: 1.0,
-            },
+            Attack::new(10.0),
+            Damage::new(15.0),
+            DamageType::Explosive,
+            ArmorPenetration(10.0),
+            BlastRadius(40.0),
             HitEffect {
-                effect: crate::fx::animated::AnimatedEffects::SmallExplosion,
+                effect: crate::fx::animated::AnimatedEffects::FlashExplosion,
             },
         ))
         .id()
 }
 
-pub fn small_pulse_laser_attack(commands: &mut Commands) -> Entity {
+/// The attack from a cannon shell. The shell has already struck, so it cannot be evaded.
+///
+/// Shells are most effective against large ships.
+pub fn cannon_shell_attack(commands: &mut Commands) -> Entity {
     commands
         .spawn((
-            Attack::new(2.0),
-            Damage::new(2.0),
-            BeamStyle {
-                effect: crate::fx::animated::AnimatedEffects::GreenLaserBeam,
-                width: 0.5,
+            Attack::new(1.0),
+            Damage::new(8.0),
+            DamageType::Kinetic,
+            Unavoidable,
+            CategoryBonus {
+                categories: AgentCategory::FRIGATE | AgentCategory::CRUISER,
+                multiplier: 1.5,
             },
             HitEffect {
                 effect: crate::fx::animated::AnimatedEffects::TinyPlusExplosion,
@@ -41,14 +54,20 @@ pub fn small_pulse_laser_attack(commands: &mut Commands) -> Entity {
         .id()
 }
 
-pub fn small_rocket_attack(commands: &mut Commands) -> Entity {
+/// The attack from a rapid-firing point-defense laser, accurate enough to track missiles.
+pub fn point_defense_attack(commands: &mut Commands) -> Entity {
     commands
         .spawn((
-            Attack::new(10.0),
-            Damage::new(15.0),
+            Attack::new(6.0),
+            Damage::new(3.0),
+            DamageType::Energy,
+            BeamStyle {
+                effect: crate::fx::animated::AnimatedEffects::GreenLaserBeam,
+                width: 0.5,
+            },
             HitEffect {
-                effect: crate::fx::animated::AnimatedEffects::FlashExplosion,
+                effect: crate::fx::animated::AnimatedEffects::TinyPlusExplosion,
             },
         ))
         .id()
-}
\ No newline at end of file
+}
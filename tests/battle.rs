@@ -0,0 +1,28 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_combat::{
+    battle::BattleState,
+    game::SimulationTick,
+    templates::ships::{asset::TemplateSpawner, spawn::TemplateName},
+};
+use common::{create_battle, run_until, spawn_ship};
+
+#[test]
+fn test_battle_waits_for_templates_to_load() {
+    let mut app = create_battle(0);
+    spawn_ship(&mut app, "ships/drone.ship.ron", Vec3::new(120.0, 0.0, 0.0), 2);
+    spawn_ship(&mut app, "ships/missing.ship.ron", Vec3::new(120.0, 20.0, 0.0), 2);
+
+    run_until(&mut app, |app| {
+        *app.world.resource::<State<BattleState>>().get() != BattleState::Setup
+    });
+
+    // the missing template is dropped rather than holding up the battle
+    let mut spawners = app.world.query::<&TemplateSpawner>();
+    assert_eq!(spawners.iter(&app.world).count(), 0);
+    let mut names = app.world.query::<&TemplateName>();
+    assert!(names.iter(&app.world).any(|name| name.0 == "drone"));
+    // only the step on which the battle started has been counted
+    assert_eq!(app.world.resource::<SimulationTick>().0, 1);
+}
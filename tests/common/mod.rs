@@ -0,0 +1,90 @@
+//! Fixtures shared by the integration tests, which simulate battles headless.
+
+// Each test file only uses some of the fixtures.
+#![allow(dead_code)]
+
+use bevy::prelude::*;
+use bevy_combat::{
+    combat::{events::CombatEvent, mortal::Health, Team},
+    game::{rng::SimulationSeed, SimulationTick},
+    headless::HeadlessCombatPlugins,
+    templates::ships::{asset::TemplateSpawner, spawn::SpawnBundle},
+};
+
+/// Updates before a test gives up on the battle reaching the state it waits for.
+const MAX_STEPS: u32 = 2000;
+
+/// Creates an app that simulates battles headless, with the plugins and resources added by `setup`.
+pub fn headless_app(setup: impl FnOnce(&mut App)) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, HeadlessCombatPlugins));
+    setup(&mut app);
+    app.finish();
+    app.cleanup();
+    app
+}
+
+/// Creates a battle between four small ships on team 1 and four drones on team 2.
+pub fn create_battle(seed: u64) -> App {
+    create_battle_with(seed, |_| {})
+}
+
+/// Creates the battle of [create_battle], with the plugins and resources added by `setup`.
+pub fn create_battle_with(seed: u64, setup: impl FnOnce(&mut App)) -> App {
+    let mut app = headless_app(|app| {
+        app.insert_resource(SimulationSeed(seed));
+        setup(app);
+    });
+    spawn_fleets(&mut app, 4);
+    app
+}
+
+/// Spawns `count` small ships on team 1 facing `count` drones on team 2.
+pub fn spawn_fleets(app: &mut App, count: usize) {
+    for i in 0..count {
+        let y = 20.0 * i as f32;
+        spawn_ship(app, "ships/small_ship.ship.ron", Vec3::new(-100.0, y, 0.0), 1);
+        spawn_ship(app, "ships/drone.ship.ron", Vec3::new(100.0, y, 0.0), 2);
+    }
+}
+
+/// Spawns the ship described by the template at `path` once it has loaded.
+pub fn spawn_ship(app: &mut App, path: &str, translation: Vec3, team: i32) {
+    let template = app.world.resource::<AssetServer>().load(path.to_string());
+    app.world.spawn(SpawnBundle {
+        spawn: TemplateSpawner(template),
+        transform: Transform::from_translation(translation),
+        team: Team(team),
+    });
+}
+
+/// Updates the app until `done` returns true, which is checked before each update.
+pub fn run_until(app: &mut App, mut done: impl FnMut(&mut App) -> bool) {
+    let mut steps = 0;
+    while !done(app) {
+        app.update();
+        steps += 1;
+        assert!(steps < MAX_STEPS, "gave up after {} steps", steps);
+    }
+}
+
+pub fn run_until_tick(app: &mut App, tick: u64) {
+    run_until(app, |app| app.world.resource::<SimulationTick>().0 >= tick);
+}
+
+/// Takes the combat events sent since the last call.
+pub fn drain_combat_events(app: &mut App) -> Vec<CombatEvent> {
+    app.world
+        .resource_mut::<Events<CombatEvent>>()
+        .drain()
+        .collect()
+}
+
+/// The team, health and position of every ship still alive.
+pub fn survivors(app: &mut App) -> Vec<(i32, f32, Vec3)> {
+    let mut query = app.world.query::<(&Team, &Health, &Transform)>();
+    query
+        .iter(&app.world)
+        .map(|(team, health, transform)| (team.0, health.0, transform.translation))
+        .collect()
+}
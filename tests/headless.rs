@@ -0,0 +1,23 @@
+mod common;
+
+use common::{create_battle, run_until_tick, survivors};
+
+#[test]
+fn test_headless_battle() {
+    let mut app = create_battle(0);
+    run_until_tick(&mut app, 600);
+
+    let survivors = survivors(&mut app);
+    let total_health: f32 = survivors.iter().map(|(_, health, _)| health).sum();
+    assert!(!survivors.is_empty());
+    assert!(total_health < 4.0 * (100.0 + 50.0));
+}
+
+#[test]
+fn test_same_seed_gives_same_battle() {
+    let mut first = create_battle(7);
+    let mut second = create_battle(7);
+    run_until_tick(&mut first, 600);
+    run_until_tick(&mut second, 600);
+    assert_eq!(survivors(&mut first), survivors(&mut second));
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use bevy::{prelude::*, utils::HashSet};
+use bevy_combat::{
+    ai::aggression::TargetingOrders,
+    combat::{events::CombatEvent, Target, Team},
+    game::SimulationTick,
+    templates::ships::spawn::TemplateName,
+};
+use common::{drain_combat_events, headless_app, run_until, spawn_ship};
+
+#[test]
+fn test_point_defense_fires_on_enemy_rockets() {
+    let mut app = headless_app(|_| {});
+    for (team, y) in [(1, 0.0), (2, 400.0)] {
+        spawn_ship(
+            &mut app,
+            "ships/rocket_frigate.ship.ron",
+            Vec3::new(0.0, y, 0.0),
+            team,
+        );
+    }
+
+    let mut defender = None;
+    let mut attacker = None;
+    let mut enemy_rockets = HashSet::new();
+    let mut acquired = false;
+    let mut fired = false;
+    run_until(&mut app, |app| {
+        for event in drain_combat_events(app) {
+            match event {
+                CombatEvent::Spawned { instigator, entity } => {
+                    let name = &app.world.get::<TemplateName>(entity).unwrap().0;
+                    if name == "rocket" {
+                        if instigator == attacker {
+                            enemy_rockets.insert(entity);
+                        }
+                    } else if app.world.get::<Team>(entity) == Some(&Team(1)) {
+                        defender = Some(entity);
+                    } else {
+                        attacker = Some(entity);
+                    }
+                }
+                CombatEvent::Hit {
+                    instigator, target, ..
+                }
+                | CombatEvent::Missed {
+                    instigator, target, ..
+                }
+                | CombatEvent::Blocked {
+                    instigator, target, ..
+                } => {
+                    fired |= instigator == defender && enemy_rockets.contains(&target);
+                }
+                _ => {}
+            }
+        }
+
+        let mut point_defense = app.world.query::<(&TargetingOrders, &Target, &Parent)>();
+        acquired |= point_defense
+            .iter(&app.world)
+            .filter(|(orders, _, parent)| orders.only_preferred && Some(parent.get()) == defender)
+            .any(|(_, target, _)| target.0.is_some_and(|target| enemy_rockets.contains(&target)));
+        app.world.resource::<SimulationTick>().0 >= 600 || (acquired && fired)
+    });
+    assert!(!enemy_rockets.is_empty());
+    assert!(acquired, "point defense never targeted a rocket");
+    assert!(fired, "point defense never fired on a rocket");
+}
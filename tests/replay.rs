@@ -0,0 +1,96 @@
+mod common;
+
+use std::fs;
+
+use bevy::prelude::*;
+use bevy_combat::{
+    battle::Vip,
+    game::{GameSpeed, SimulationTick},
+    replay::{ReplayPlugin, ReplayRecorder},
+    scenario::{Scenario, ScenarioPlugin},
+    templates::ships::asset::SHIP_TEMPLATE_EXTENSION,
+};
+use common::{headless_app, run_until_tick, spawn_fleets, survivors};
+
+#[test]
+fn test_replay_reproduces_battle() {
+    let path = std::env::temp_dir().join("bevy_combat_test_replay.ron");
+
+    let mut recorded = headless_app(|app| {
+        app.add_plugins(ReplayPlugin::Record(path.clone()));
+    });
+    spawn_fleets(&mut recorded, 3);
+    run_until_tick(&mut recorded, 200);
+    recorded.world.resource_mut::<GameSpeed>().0 = 3;
+    run_until_tick(&mut recorded, 400);
+    recorded
+        .world
+        .resource::<ReplayRecorder>()
+        .replay
+        .save(&path)
+        .unwrap();
+
+    let mut played = headless_app(|app| {
+        app.add_plugins(ReplayPlugin::Playback(path.clone()));
+    });
+    run_until_tick(&mut played, 400);
+
+    assert_eq!(
+        recorded.world.resource::<SimulationTick>().0,
+        played.world.resource::<SimulationTick>().0
+    );
+    assert_eq!(survivors(&mut recorded), survivors(&mut played));
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn test_replay_reproduces_scenario_with_template_vips() {
+    let path = std::env::temp_dir().join("bevy_combat_test_scenario_replay.ron");
+    let scenario = Scenario::parse(
+        r#"(
+            fleets: [
+                (
+                    team: 1,
+                    ship: Template("ships/small_ship.ship.ron"),
+                    count: 2,
+                    area: (centre: (-100.0, 0.0), half_size: (20.0, 60.0)),
+                    vip: true,
+                ),
+                (
+                    team: 2,
+                    ship: Template("ships/drone.ship.ron"),
+                    count: 4,
+                    area: (centre: (100.0, 0.0), half_size: (20.0, 60.0)),
+                ),
+            ],
+            victory_conditions: [ProtectVip(team: 1)],
+        )"#,
+    )
+    .unwrap();
+
+    let mut recorded = headless_app(|app| {
+        app.add_plugins((
+            ScenarioPlugin::Run(scenario.clone()),
+            ReplayPlugin::Record(path.clone()),
+        ));
+    });
+    run_until_tick(&mut recorded, 300);
+    let replay = recorded.world.resource::<ReplayRecorder>().replay.clone();
+    assert_eq!(replay.spawns.len(), 6);
+    assert_eq!(replay.spawns.iter().filter(|spawn| spawn.vip).count(), 2);
+    assert!(replay
+        .spawns
+        .iter()
+        .all(|spawn| spawn.template.ends_with(SHIP_TEMPLATE_EXTENSION)));
+    replay.save(&path).unwrap();
+
+    let mut played = headless_app(|app| {
+        app.add_plugins((ScenarioPlugin::Run(scenario), ReplayPlugin::Playback(path.clone())));
+    });
+    run_until_tick(&mut played, 300);
+
+    assert_eq!(survivors(&mut recorded), survivors(&mut played));
+    let mut vips = played.world.query_filtered::<(), With<Vip>>();
+    assert_eq!(vips.iter(&played.world).count(), 2);
+    let _ = fs::remove_file(path);
+}
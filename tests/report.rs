@@ -0,0 +1,33 @@
+mod common;
+
+use bevy::{app::AppExit, prelude::*};
+use bevy_combat::{
+    battle::{BattleResult, VictoryCondition, VictoryConditions},
+    report::BattleReportPlugin,
+};
+use common::{create_battle_with, run_until};
+
+#[test]
+fn test_battle_exits_when_finished_and_writes_report() {
+    let path = std::env::temp_dir().join("bevy_combat_test_report.json");
+    let mut app = create_battle_with(3, |app| {
+        app.add_plugins(BattleReportPlugin(path.clone()))
+            .insert_resource(VictoryConditions(vec![
+                VictoryCondition::Annihilation,
+                VictoryCondition::TimeLimit(20.0),
+            ]));
+    });
+    run_until(&mut app, |app| {
+        !app.world.resource::<Events<AppExit>>().is_empty()
+    });
+
+    let result = *app.world.resource::<BattleResult>();
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(report["winner"].as_i64(), result.winner.map(i64::from));
+    assert_eq!(
+        report["duration"].as_f64().map(|duration| duration as f32),
+        Some(result.duration)
+    );
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,30 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_combat::{
+    battle::{BattleState, VictoryConditions},
+    combat::{mortal::Health, Team},
+    scenario::{Scenario, ScenarioPlugin},
+};
+use common::{headless_app, run_until};
+
+#[test]
+fn test_scenario_asset_starts_the_battle() {
+    let mut app = headless_app(|app| {
+        app.add_plugins(ScenarioPlugin::Load(
+            "scenarios/default.scenario.ron".to_string(),
+        ));
+    });
+    run_until(&mut app, |app| {
+        *app.world.resource::<State<BattleState>>().get() != BattleState::Setup
+    });
+
+    let mut teams = app.world.query_filtered::<&Team, With<Health>>();
+    let mut count = |team| teams.iter(&app.world).filter(|t| **t == Team(team)).count();
+    assert_eq!((count(1), count(2)), (20, 60));
+    assert_eq!(
+        app.world.resource::<Scenario>().path.as_deref(),
+        Some("scenarios/default.scenario.ron")
+    );
+    assert!(!app.world.resource::<VictoryConditions>().0.is_empty());
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_combat::{
+    combat::events::CombatEvent, game::SimulationTick, templates::ships::spawn::TemplateName,
+};
+use common::{drain_combat_events, headless_app, run_until, spawn_ship};
+
+#[test]
+fn test_cannon_shells_hit_the_enemy_and_not_their_ship() {
+    let mut app = headless_app(|_| {});
+    spawn_ship(&mut app, "ships/gunboat.ship.ron", Vec3::ZERO, 1);
+    // a repair tender never fires on an enemy, so every hit comes from the gunboat
+    spawn_ship(
+        &mut app,
+        "ships/repair_tender.ship.ron",
+        Vec3::new(0.0, 150.0, 0.0),
+        2,
+    );
+
+    let mut gunboat = None;
+    let mut hits_on_enemy = 0;
+    run_until(&mut app, |app| {
+        for event in drain_combat_events(app) {
+            match event {
+                CombatEvent::Spawned { entity, .. }
+                    if app.world.get::<TemplateName>(entity).unwrap().0 == "gunboat" =>
+                {
+                    gunboat = Some(entity);
+                }
+                CombatEvent::Hit {
+                    instigator, target, ..
+                }
+                | CombatEvent::Blocked {
+                    instigator, target, ..
+                } => {
+                    assert_ne!(Some(target), gunboat, "the gunboat shot itself");
+                    assert_eq!(instigator, gunboat);
+                    hits_on_enemy += 1;
+                }
+                _ => {}
+            }
+        }
+        app.world.resource::<SimulationTick>().0 >= 300
+    });
+    assert!(hits_on_enemy > 0);
+}